@@ -0,0 +1,187 @@
+//! Derive macros for `rs_openshowvar`.
+//!
+//! Use them through the `derive` feature of `rs_openshowvar`, which re-exports
+//! `KrlStruct` and `KrlEnum` next to the `FromKrl` and `ToKrl` traits they implement.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Implements `FromKrl` and `ToKrl` for a struct with named fields, mapping
+/// it to a KRL `STRUC` literal such as `{PART_T: ID 7, NAME[] "bolt"}`.
+///
+/// The KRL type name defaults to the struct name in upper case and member
+/// names default to the field names in upper case. Both can be changed with
+/// `#[krl(rename = "...")]`. Fields of type `Option` may be missing when
+/// reading and are left out when writing, which allows partial writes.
+#[proc_macro_derive(KrlStruct, attributes(krl))]
+pub fn derive_krl_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_struct(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `FromKrl` and `ToKrl` for an enum of unit variants, mapping it
+/// to a KRL `ENUM` value such as `#ACTIVE`.
+///
+/// Values default to the variant names in upper case and can be changed with
+/// `#[krl(rename = "...")]`.
+#[proc_macro_derive(KrlEnum, attributes(krl))]
+pub fn derive_krl_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_enum(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Generates the trait implementations for a struct.
+fn expand_struct(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "KrlStruct requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "KrlStruct can only be derived for structs",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let type_name = krl_name(&input.attrs, &name.to_string())?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut reads = Vec::new();
+    let mut writes = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let member = krl_name(&field.attrs, &ident.to_string())?;
+        reads.push(quote! {
+            #ident: value.get(#member)?,
+        });
+        writes.push(quote! {
+            if let Some(value) = ::rs_openshowvar::ToKrl::to_krl_value(&self.#ident)? {
+                fields.push((#member.to_string(), value));
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::rs_openshowvar::FromKrl for #name #ty_generics #where_clause {
+            fn from_krl(
+                value: &::rs_openshowvar::KrlValue,
+            ) -> ::rs_openshowvar::Result<Self> {
+                if value.fields().is_none() {
+                    return Err(::rs_openshowvar::Error::UnexpectedType {
+                        expected: #type_name,
+                        found: value.to_string(),
+                    });
+                }
+                Ok(#name {
+                    #(#reads)*
+                })
+            }
+        }
+
+        impl #impl_generics ::rs_openshowvar::ToKrl for #name #ty_generics #where_clause {
+            fn to_krl_value(
+                &self,
+            ) -> ::rs_openshowvar::Result<Option<::rs_openshowvar::KrlValue>> {
+                let mut fields = Vec::new();
+                #(#writes)*
+                Ok(Some(::rs_openshowvar::KrlValue::Struct {
+                    type_name: Some(#type_name.to_string()),
+                    fields,
+                }))
+            }
+        }
+    })
+}
+
+/// Generates the trait implementations for an enum.
+fn expand_enum(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "KrlEnum can only be derived for enums",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let type_name = krl_name(&input.attrs, &name.to_string())?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut reads = Vec::new();
+    let mut writes = Vec::new();
+    for variant in variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "KrlEnum variants cannot have fields",
+            ));
+        }
+        let ident = &variant.ident;
+        let value = krl_name(&variant.attrs, &ident.to_string())?;
+        reads.push(quote! {
+            Some(name) if name.eq_ignore_ascii_case(#value) => Ok(#name::#ident),
+        });
+        writes.push(quote! {
+            #name::#ident => #value,
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::rs_openshowvar::FromKrl for #name #ty_generics #where_clause {
+            fn from_krl(
+                value: &::rs_openshowvar::KrlValue,
+            ) -> ::rs_openshowvar::Result<Self> {
+                match value.as_enum() {
+                    #(#reads)*
+                    _ => Err(::rs_openshowvar::Error::UnexpectedType {
+                        expected: #type_name,
+                        found: value.to_string(),
+                    }),
+                }
+            }
+        }
+
+        impl #impl_generics ::rs_openshowvar::ToKrl for #name #ty_generics #where_clause {
+            fn to_krl_value(
+                &self,
+            ) -> ::rs_openshowvar::Result<Option<::rs_openshowvar::KrlValue>> {
+                let value = match self {
+                    #(#writes)*
+                };
+                Ok(Some(::rs_openshowvar::KrlValue::Enum(value.to_string())))
+            }
+        }
+    })
+}
+
+/// Returns the KRL name given with `#[krl(rename = "...")]`, or `default` in upper case.
+fn krl_name(attrs: &[Attribute], default: &str) -> syn::Result<String> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("krl")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported krl attribute, expected `rename`"))
+            }
+        })?;
+    }
+    Ok(name.unwrap_or_else(|| default.trim_start_matches("r#").to_ascii_uppercase()))
+}
@@ -0,0 +1,65 @@
+use std::ops::RangeInclusive;
+
+use crate::error::{Error, Result};
+
+/// Maximum number of dimensions of a KRL array.
+pub const MAX_ARRAY_DIMENSIONS: usize = 3;
+
+/// Highest array index accepted in a range.
+pub const MAX_ARRAY_INDEX: u32 = 65_535;
+
+/// Maximum number of elements read or written in one call.
+pub const MAX_ARRAY_ELEMENTS: usize = 65_536;
+
+/// Returns the names of the elements of `base` covered by `dims`, in row-major order.
+///
+/// Each range gives the first and last index of one dimension. KRL indices
+/// start at 1, so the element names run from `base[1,1]` upwards, with the
+/// last index changing fastest.
+pub(crate) fn element_names(base: &str, dims: &[RangeInclusive<u32>]) -> Result<Vec<String>> {
+    if base.is_empty() {
+        return Err(Error::InvalidInput("Empty variable name"));
+    }
+    if base.contains(['[', ']']) {
+        return Err(Error::InvalidInput("Array name must not contain an index"));
+    }
+    if dims.is_empty() || dims.len() > MAX_ARRAY_DIMENSIONS {
+        return Err(Error::InvalidInput("Arrays have one to three dimensions"));
+    }
+    let mut count: usize = 1;
+    for dim in dims {
+        if *dim.start() == 0 {
+            return Err(Error::InvalidInput("Array indices start at 1"));
+        }
+        if dim.is_empty() {
+            return Err(Error::InvalidInput("Empty index range"));
+        }
+        if *dim.end() > MAX_ARRAY_INDEX {
+            return Err(Error::InvalidInput("Array index exceeds MAX_ARRAY_INDEX"));
+        }
+        // Check the element count before building any names
+        let len = (dim.end() - dim.start()) as usize + 1;
+        count = count
+            .checked_mul(len)
+            .filter(|&count| count <= MAX_ARRAY_ELEMENTS)
+            .ok_or(Error::InvalidInput("Too many array elements"))?;
+    }
+
+    // Build the names dimension by dimension, so the last index changes fastest
+    let mut names = vec![String::new()];
+    for dim in dims {
+        names = names
+            .iter()
+            .flat_map(|prefix| {
+                dim.clone().map(move |index| match prefix.is_empty() {
+                    true => index.to_string(),
+                    false => format!("{},{}", prefix, index),
+                })
+            })
+            .collect();
+    }
+    Ok(names
+        .into_iter()
+        .map(|indices| format!("{}[{}]", base, indices))
+        .collect())
+}
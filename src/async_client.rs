@@ -0,0 +1,275 @@
+use socket2::{SockRef, TcpKeepalive};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::builder::Config;
+use crate::codec::{self, Mode, Request, Response, HEADER_LEN};
+use crate::error::{Error, Result};
+use crate::rs_openshowvar::{frame_too_large, response_value};
+
+/// The `AsyncOpenShowVar` structure is the asynchronous counterpart of `OpenShowVar`, built on tokio.
+///
+/// It uses the same `codec`, message ID tracking, frame limits and input
+/// validation as the blocking client, so both behave identically on the wire.
+/// Timeouts from the `OpenShowVarBuilder` are applied with `tokio::time::timeout`.
+pub struct AsyncOpenShowVar {
+    /// IP address for the TCP connection.
+    tcp_ip: String,
+    /// Port number for the TCP connection.
+    tcp_port: u16,
+    /// TCP connection.
+    conn: Option<TcpStream>,
+    /// Timeouts, socket options and frame limits.
+    config: Config,
+    /// Message ID used for the next request on this connection.
+    next_msg_id: u16,
+    /// Number of responses discarded because their message ID did not match.
+    stale_responses: u64,
+}
+
+impl AsyncOpenShowVar {
+    /// Creates a new instance of `AsyncOpenShowVar` with default settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `tcp_ip` - IP address of the TCP server to connect to.
+    /// * `tcp_port` - Port number of the TCP server to connect to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::AsyncOpenShowVar;
+    /// let osv = AsyncOpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// ```
+    pub fn new(tcp_ip: String, tcp_port: u16) -> AsyncOpenShowVar {
+        AsyncOpenShowVar::with_config(tcp_ip, tcp_port, Config::default())
+    }
+
+    /// Creates a new, unconnected instance using the given settings.
+    pub(crate) fn with_config(tcp_ip: String, tcp_port: u16, config: Config) -> AsyncOpenShowVar {
+        AsyncOpenShowVar {
+            tcp_ip,
+            tcp_port,
+            conn: None,
+            config,
+            next_msg_id: 0,
+            stale_responses: 0,
+        }
+    }
+
+    /// Connects to the TCP server.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the TCP connection is successful.
+    /// Returns `Error` if the TCP connection fails or times out.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rs_openshowvar::AsyncOpenShowVar;
+    /// # async fn run() {
+    /// let mut osv = AsyncOpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// match osv.connect().await {
+    ///     Ok(_) => println!("Connection successful"),
+    ///     Err(e) => println!("Connection error: {}", e),
+    /// }
+    /// # }
+    /// ```
+    pub async fn connect(&mut self) -> Result<()> {
+        let addr = (self.tcp_ip.as_str(), self.tcp_port);
+        let stream = timeout(self.config.connect_timeout, TcpStream::connect(addr))
+            .await
+            .map_err(|_| Error::Timeout)??;
+
+        // Apply the socket options to the connection
+        stream.set_nodelay(self.config.nodelay)?;
+        if let Some(idle) = self.config.keepalive {
+            SockRef::from(&stream).set_tcp_keepalive(&TcpKeepalive::new().with_time(idle))?;
+        }
+        self.conn = Some(stream);
+        // Message IDs are tracked per connection
+        self.next_msg_id = 0;
+        Ok(())
+    }
+
+    /// Checks if a connection to the TCP server exists.
+    pub fn is_connected(&self) -> bool {
+        self.conn.is_some()
+    }
+
+    /// Returns the number of responses discarded because their message ID
+    /// did not match the request that was waiting for an answer.
+    pub fn stale_responses(&self) -> u64 {
+        self.stale_responses
+    }
+
+    /// Sends a single request and returns the parsed response.
+    ///
+    /// A failure reported by the server is returned in the `success` field of
+    /// the response rather than as an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - Whether the variable is read or written.
+    /// * `var_name` - Name of the variable.
+    /// * `val` - Value to write. Ignored for reads.
+    pub async fn exchange(&mut self, mode: Mode, var_name: &str, val: &str) -> Result<Response> {
+        // Take the next message ID so the response can be matched to this request
+        let msg_id = self.next_msg_id;
+        self.next_msg_id = self.next_msg_id.wrapping_add(1);
+        let request = codec::encode(&Request {
+            msg_id,
+            mode,
+            name: var_name.to_string(),
+            value: val.to_string(),
+        })?;
+
+        let conn = self.conn.as_mut().ok_or(Error::NotConnected)?;
+        let written = timeout(self.config.write_timeout, conn.write_all(&request))
+            .await
+            .map_err(|_| Error::Timeout)
+            .and_then(|written| written.map_err(Error::from));
+        // A write that fails or times out may have sent part of the frame,
+        // so the server would read the next request out of step
+        if let Err(e) = written {
+            self.conn = None;
+            return Err(e);
+        }
+
+        // Read frames until the one answering this request arrives.
+        // Replies carrying another ID belong to earlier, timed-out requests.
+        loop {
+            let mut partial = false;
+            let frame = timeout(
+                self.config.read_timeout,
+                read_frame(conn, self.config.max_frame_size, &mut partial),
+            )
+            .await
+            .map_err(|_| Error::Timeout)
+            .and_then(|frame| frame);
+            // A frame cut off by the timeout or a broken socket leaves the
+            // stream misaligned, so the connection has to be dropped
+            let frame = match frame {
+                Ok(frame) => frame,
+                Err(e) => {
+                    if partial || matches!(e, Error::Io(_)) {
+                        self.conn = None;
+                    }
+                    return Err(e);
+                }
+            };
+            if codec::header(&frame).map(|(id, _)| id) == Some(msg_id) {
+                return codec::decode(&frame);
+            }
+            self.stale_responses += 1;
+        }
+    }
+
+    /// Reads the specified variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name` - Name of the variable to read.
+    ///
+    /// # Returns
+    ///
+    /// Returns the read variable value inside `Result<String>`.
+    /// Returns `Error::VariableNotFound` if the server cannot read the variable.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rs_openshowvar::AsyncOpenShowVar;
+    /// # async fn run() {
+    /// let mut osv = AsyncOpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// osv.connect().await.unwrap();
+    /// match osv.read("$OV_PRO").await {
+    ///     Ok(val) => println!("Read value: {}", val),
+    ///     Err(e) => println!("Reading error: {}", e),
+    /// }
+    /// # }
+    /// ```
+    pub async fn read(&mut self, var_name: &str) -> Result<String> {
+        // Return error if variable name to read is empty
+        if var_name.is_empty() {
+            return Err(Error::InvalidInput("Empty variable name"));
+        }
+
+        let response = self.exchange(Mode::Read, var_name, "").await?;
+        response_value(response, var_name, "")
+    }
+
+    /// Writes a value to the specified variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name` - Name of the variable to write.
+    /// * `val` - Value to write to the variable.
+    ///
+    /// # Returns
+    ///
+    /// Returns the written variable value inside `Result<String>`.
+    /// Returns `Error::WriteRejected` if the server does not accept the value.
+    pub async fn write(&mut self, var_name: &str, val: &str) -> Result<String> {
+        // Return error if variable name to write is empty
+        if var_name.is_empty() {
+            return Err(Error::InvalidInput("Empty variable name"));
+        }
+
+        // Return error if value to write is empty
+        if val.is_empty() {
+            return Err(Error::InvalidInput("Empty value"));
+        }
+
+        let response = self.exchange(Mode::Write, var_name, val).await?;
+        response_value(response, var_name, val)
+    }
+
+    /// Terminates the TCP connection.
+    pub async fn disconnect(&mut self) {
+        // Close the connection if it exists
+        if let Some(mut conn) = self.conn.take() {
+            let _ = conn.shutdown().await;
+        }
+    }
+}
+
+/// Reads one complete frame (header and body) from the connection.
+///
+/// Mirrors the blocking reader: frames whose body exceeds `max_frame_size`
+/// are drained from the stream and rejected, and `partial` is set while a
+/// frame has been started but not completely read. The first read is cancel
+/// safe, so a timeout before any byte arrived leaves the stream aligned.
+async fn read_frame(
+    conn: &mut TcpStream,
+    max_frame_size: usize,
+    partial: &mut bool,
+) -> Result<Vec<u8>> {
+    let mut header = [0u8; HEADER_LEN];
+    let started = conn.read(&mut header).await?;
+    if started == 0 {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    *partial = true;
+    conn.read_exact(&mut header[started..]).await?;
+    let (_, msg_len) = codec::header(&header).expect("header buffer is complete");
+
+    // Skip the oversized body so the stream stays aligned on frame boundaries
+    if msg_len > max_frame_size {
+        tokio::io::copy(
+            &mut (&mut *conn).take(msg_len as u64),
+            &mut tokio::io::sink(),
+        )
+        .await?;
+        *partial = false;
+        return Err(frame_too_large(msg_len, max_frame_size));
+    }
+
+    let mut frame = vec![0u8; HEADER_LEN + msg_len];
+    frame[..HEADER_LEN].copy_from_slice(&header);
+    conn.read_exact(&mut frame[HEADER_LEN..]).await?;
+    *partial = false;
+    Ok(frame)
+}
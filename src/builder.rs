@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+#[cfg(feature = "tokio")]
+use crate::async_client::AsyncOpenShowVar;
+use crate::error::Result;
+use crate::health::{DEFAULT_PROBE_TIMEOUT, DEFAULT_PROBE_VARIABLE};
+use crate::reconnect::ReconnectPolicy;
+use crate::OpenShowVar;
+
+/// Default upper bound for the body length of a single received frame.
+pub const DEFAULT_MAX_FRAME_SIZE: usize = u16::MAX as usize;
+
+/// Connection settings shared by `OpenShowVarBuilder` and `OpenShowVar`.
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    /// Maximum time to wait for the TCP connection to be established.
+    pub(crate) connect_timeout: Duration,
+    /// Maximum time a single read on the connection may block.
+    pub(crate) read_timeout: Duration,
+    /// Maximum time a single write on the connection may block.
+    pub(crate) write_timeout: Duration,
+    /// Whether Nagle's algorithm is disabled on the connection.
+    pub(crate) nodelay: bool,
+    /// Idle time before TCP keepalive probes are sent, if enabled.
+    pub(crate) keepalive: Option<Duration>,
+    /// Maximum accepted body length of a received frame.
+    pub(crate) max_frame_size: usize,
+    /// How lost connections are healed, if at all.
+    pub(crate) reconnect: Option<ReconnectPolicy>,
+    /// Variable read by the active health probe.
+    pub(crate) probe_variable: String,
+    /// Maximum time the active health probe waits for an answer.
+    pub(crate) probe_timeout: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            connect_timeout: Duration::from_secs(5),
+            read_timeout: Duration::from_secs(2),
+            write_timeout: Duration::from_secs(2),
+            nodelay: false,
+            keepalive: None,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            reconnect: None,
+            probe_variable: DEFAULT_PROBE_VARIABLE.to_string(),
+            probe_timeout: DEFAULT_PROBE_TIMEOUT,
+        }
+    }
+}
+
+/// The `OpenShowVarBuilder` structure is used to configure an `OpenShowVar` before connecting.
+///
+/// Settings that are not changed keep the defaults used by `OpenShowVar::new`:
+/// a 5-second connect timeout, 2-second read and write timeouts, Nagle's
+/// algorithm enabled, no TCP keepalive, `DEFAULT_MAX_FRAME_SIZE`, no
+/// automatic reconnect and a health probe reading `$MODE_OP` within 500 ms.
+#[derive(Debug, Clone)]
+pub struct OpenShowVarBuilder {
+    /// IP address for the TCP connection.
+    tcp_ip: String,
+    /// Port number for the TCP connection.
+    tcp_port: u16,
+    /// Settings applied to the built `OpenShowVar`.
+    config: Config,
+}
+
+impl OpenShowVarBuilder {
+    /// Creates a new builder with default settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `tcp_ip` - IP address of the TCP server to connect to.
+    /// * `tcp_port` - Port number of the TCP server to connect to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVarBuilder;
+    /// let builder = OpenShowVarBuilder::new("127.0.0.1".to_string(), 7000);
+    /// ```
+    pub fn new(tcp_ip: String, tcp_port: u16) -> OpenShowVarBuilder {
+        OpenShowVarBuilder {
+            tcp_ip,
+            tcp_port,
+            config: Config::default(),
+        }
+    }
+
+    /// Sets the maximum time to wait for the TCP connection to be established.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum time to wait for data from the server during a request.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.config.read_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum time to wait for a request to be written to the connection.
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.config.write_timeout = timeout;
+        self
+    }
+
+    /// Enables or disables `TCP_NODELAY`, sending small requests without delay.
+    pub fn nodelay(mut self, nodelay: bool) -> Self {
+        self.config.nodelay = nodelay;
+        self
+    }
+
+    /// Enables TCP keepalive probes after the connection has been idle for `idle`.
+    pub fn keepalive(mut self, idle: Duration) -> Self {
+        self.config.keepalive = Some(idle);
+        self
+    }
+
+    /// Sets the maximum body length accepted for a received frame.
+    pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.config.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Enables automatic reconnects according to the given policy.
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.config.reconnect = Some(policy);
+        self
+    }
+
+    /// Sets the variable read by `OpenShowVar::health` and how long to wait for its answer.
+    pub fn health_probe(mut self, var_name: &str, timeout: Duration) -> Self {
+        self.config.probe_variable = var_name.to_string();
+        self.config.probe_timeout = timeout;
+        self
+    }
+
+    /// Creates a configured `OpenShowVar` without connecting it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVarBuilder;
+    /// use std::time::Duration;
+    /// let osv = OpenShowVarBuilder::new("127.0.0.1".to_string(), 7000)
+    ///     .connect_timeout(Duration::from_secs(1))
+    ///     .read_timeout(Duration::from_millis(500))
+    ///     .nodelay(true)
+    ///     .build();
+    /// assert!(!osv.is_connected());
+    /// ```
+    pub fn build(self) -> OpenShowVar {
+        OpenShowVar::with_config(self.tcp_ip, self.tcp_port, self.config)
+    }
+
+    /// Creates a configured `OpenShowVar` and connects it.
+    ///
+    /// # Returns
+    ///
+    /// Returns the connected `OpenShowVar`.
+    /// Returns `Error` if the TCP connection fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVarBuilder;
+    /// use std::time::Duration;
+    /// match OpenShowVarBuilder::new("127.0.0.1".to_string(), 7000)
+    ///     .connect_timeout(Duration::from_millis(200))
+    ///     .connect()
+    /// {
+    ///     Ok(_) => println!("Connection successful"),
+    ///     Err(e) => println!("Connection error: {}", e),
+    /// }
+    /// ```
+    pub fn connect(self) -> Result<OpenShowVar> {
+        let mut osv = self.build();
+        osv.connect()?;
+        Ok(osv)
+    }
+
+    /// Creates a configured `AsyncOpenShowVar` without connecting it.
+    ///
+    /// The async client uses the same timeouts, socket options and frame
+    /// limits. Automatic reconnects and the health probe only apply to `OpenShowVar`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVarBuilder;
+    /// use std::time::Duration;
+    /// let osv = OpenShowVarBuilder::new("127.0.0.1".to_string(), 7000)
+    ///     .read_timeout(Duration::from_millis(500))
+    ///     .build_async();
+    /// assert!(!osv.is_connected());
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn build_async(self) -> AsyncOpenShowVar {
+        AsyncOpenShowVar::with_config(self.tcp_ip, self.tcp_port, self.config)
+    }
+}
@@ -0,0 +1,398 @@
+//! Encoding and decoding of KukaVarProxy messages.
+//!
+//! The functions in this module operate on byte slices only and never touch a
+//! socket, so they can be reused by other transports, mock servers or proxies.
+//!
+//! Every message starts with a 4-byte header holding the message ID and the
+//! length of the body that follows. A request body contains the mode, the
+//! variable name and, for writes, the value. A response body contains the
+//! mode, the value and a 2-byte status trailer.
+
+use crate::error::{Error, Result};
+
+/// Length of the message header (message ID and body length).
+pub const HEADER_LEN: usize = 4;
+
+/// Length of the status trailer at the end of a response body.
+pub const TRAILER_LEN: usize = 2;
+
+/// Maximum length of a message body, limited by the 2-byte length field of the header.
+pub const MAX_BODY_LEN: usize = u16::MAX as usize;
+
+/// Maximum length of a single KRL identifier, such as a variable or structure member name.
+pub const MAX_IDENTIFIER_LEN: usize = 24;
+
+/// Status trailer sent when a request succeeded.
+const TRAILER_SUCCESS: [u8; TRAILER_LEN] = [0x00, 0x01];
+
+/// Status trailer sent when a request failed.
+const TRAILER_FAILURE: [u8; TRAILER_LEN] = [0x00, 0x00];
+
+/// Operation carried by a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Read the value of a variable.
+    Read,
+    /// Write a value to a variable.
+    Write,
+}
+
+impl Mode {
+    /// Returns the byte used for this mode on the wire.
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Mode::Read => 0,
+            Mode::Write => 1,
+        }
+    }
+
+    /// Parses the mode byte of a message.
+    pub fn from_byte(byte: u8) -> Result<Mode> {
+        match byte {
+            0 => Ok(Mode::Read),
+            1 => Ok(Mode::Write),
+            _ => Err(invalid_data("Unknown message mode")),
+        }
+    }
+}
+
+/// A request sent from a client to KukaVarProxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// Message ID echoed back in the response.
+    pub msg_id: u16,
+    /// Whether the variable is read or written.
+    pub mode: Mode,
+    /// Name of the variable.
+    pub name: String,
+    /// Value to write. Empty for reads.
+    pub value: String,
+}
+
+impl Request {
+    /// Creates a read request for the specified variable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::codec::{Mode, Request};
+    /// let request = Request::read(1, "$OV_PRO");
+    /// assert_eq!(request.mode, Mode::Read);
+    /// ```
+    pub fn read(msg_id: u16, name: &str) -> Request {
+        Request {
+            msg_id,
+            mode: Mode::Read,
+            name: name.to_string(),
+            value: String::new(),
+        }
+    }
+
+    /// Creates a write request for the specified variable and value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::codec::{Mode, Request};
+    /// let request = Request::write(1, "$OV_PRO", "50");
+    /// assert_eq!(request.mode, Mode::Write);
+    /// ```
+    pub fn write(msg_id: u16, name: &str, value: &str) -> Request {
+        Request {
+            msg_id,
+            mode: Mode::Write,
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+/// A response sent from KukaVarProxy to a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// Message ID of the request being answered.
+    pub msg_id: u16,
+    /// Mode of the request being answered.
+    pub mode: Mode,
+    /// Current value of the variable.
+    pub value: String,
+    /// Whether the server reported the request as successful.
+    pub success: bool,
+}
+
+/// Parses a message header.
+///
+/// # Returns
+///
+/// Returns the message ID and the body length, or `None` if `buf` holds fewer
+/// than `HEADER_LEN` bytes.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::codec;
+/// assert_eq!(codec::header(&[0, 7, 0, 12]), Some((7, 12)));
+/// ```
+pub fn header(buf: &[u8]) -> Option<(u16, usize)> {
+    if buf.len() < HEADER_LEN {
+        return None;
+    }
+    let msg_id = u16::from_be_bytes([buf[0], buf[1]]);
+    let body_len = u16::from_be_bytes([buf[2], buf[3]]) as usize;
+    Some((msg_id, body_len))
+}
+
+/// Returns the total length of the first frame in `buf`, header included,
+/// or `None` if the header is not complete yet.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::codec;
+/// assert_eq!(codec::frame_len(&[0, 7, 0, 12]), Some(16));
+/// assert_eq!(codec::frame_len(&[0, 7]), None);
+/// ```
+pub fn frame_len(buf: &[u8]) -> Option<usize> {
+    header(buf).map(|(_, body_len)| HEADER_LEN + body_len)
+}
+
+/// Encodes a request into a complete frame.
+///
+/// The variable name is checked with `validate_name`, and the name and value
+/// must fit into the body together with the mode and the length fields.
+///
+/// # Returns
+///
+/// Returns the encoded frame.
+/// Returns `Error::InvalidName` or `Error::ValueTooLong` if the request cannot be encoded.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::codec::{self, Request};
+/// let frame = codec::encode(&Request::read(1, "$OV_PRO")).unwrap();
+/// assert_eq!(&frame[..4], &[0, 1, 0, 10]);
+/// ```
+pub fn encode(request: &Request) -> Result<Vec<u8>> {
+    validate_name(&request.name)?;
+    // Mode, name, value and their length fields all share the body
+    let fixed = match request.mode {
+        Mode::Read => 1 + 2,
+        Mode::Write => 1 + 2 + 2,
+    };
+    let max = MAX_BODY_LEN
+        .checked_sub(fixed + request.name.len())
+        .ok_or_else(|| Error::InvalidName {
+            name: request.name.clone(),
+            reason: "name exceeds the maximum message length",
+        })?;
+    let mut body = vec![request.mode.to_byte()];
+    push_field(&mut body, request.name.as_bytes())?;
+    if request.mode == Mode::Write {
+        if request.value.len() > max {
+            return Err(Error::ValueTooLong {
+                len: request.value.len(),
+                max,
+            });
+        }
+        push_field(&mut body, request.value.as_bytes())?;
+    }
+    frame(request.msg_id, body)
+}
+
+/// Checks that a variable name can be sent to the controller.
+///
+/// The name must be non-empty printable ASCII, and every identifier in it
+/// (the parts separated by `.`, `[`, `,` and `]`) must not exceed
+/// `MAX_IDENTIFIER_LEN` characters, as required by KRL.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::codec;
+/// assert!(codec::validate_name("$TOOL_DATA[1].X").is_ok());
+/// assert!(codec::validate_name("THIS_IDENTIFIER_IS_FAR_TOO_LONG").is_err());
+/// ```
+pub fn validate_name(name: &str) -> Result<()> {
+    let invalid = |reason| Error::InvalidName {
+        name: name.to_string(),
+        reason,
+    };
+    if name.is_empty() {
+        return Err(invalid("name is empty"));
+    }
+    if !name.bytes().all(|byte| (32..=126).contains(&byte)) {
+        return Err(invalid(
+            "name contains non-printable or non-ASCII characters",
+        ));
+    }
+    let mut identifiers =
+        name.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'));
+    if identifiers.any(|identifier| identifier.len() > MAX_IDENTIFIER_LEN) {
+        return Err(invalid("identifier exceeds 24 characters"));
+    }
+    Ok(())
+}
+
+/// Decodes a complete response frame.
+///
+/// # Returns
+///
+/// Returns the parsed `Response`.
+/// Returns `Error::ProtocolViolation` if the frame is truncated, malformed
+/// or does not end with a valid status trailer.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::codec;
+/// let response = codec::decode(&[0, 1, 0, 7, 0, 0, 2, b'5', b'0', 0, 1]).unwrap();
+/// assert_eq!(response.value, "50");
+/// assert!(response.success);
+/// ```
+pub fn decode(frame: &[u8]) -> Result<Response> {
+    let (msg_id, body) = split_frame(frame)?;
+    let mode = Mode::from_byte(
+        *body
+            .first()
+            .ok_or_else(|| invalid_data("Invalid response length"))?,
+    )?;
+    let (value, trailer) = take_field(&body[1..])?;
+    let success = match trailer {
+        t if t == TRAILER_SUCCESS => true,
+        t if t == TRAILER_FAILURE => false,
+        _ => return Err(invalid_data("Invalid status trailer")),
+    };
+    Ok(Response {
+        msg_id,
+        mode,
+        value: String::from_utf8_lossy(value).to_string(),
+        success,
+    })
+}
+
+/// Encodes a response into a complete frame, as sent by a server.
+///
+/// # Returns
+///
+/// Returns the encoded frame.
+/// Returns `Error::ValueTooLong` if the value does not fit into a message body.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::codec::{self, Mode, Response};
+/// let response = Response {
+///     msg_id: 1,
+///     mode: Mode::Read,
+///     value: "50".to_string(),
+///     success: true,
+/// };
+/// let frame = codec::encode_response(&response).unwrap();
+/// assert_eq!(codec::decode(&frame).unwrap(), response);
+/// ```
+pub fn encode_response(response: &Response) -> Result<Vec<u8>> {
+    let max = MAX_BODY_LEN - 1 - 2 - TRAILER_LEN;
+    if response.value.len() > max {
+        return Err(Error::ValueTooLong {
+            len: response.value.len(),
+            max,
+        });
+    }
+    let mut body = vec![response.mode.to_byte()];
+    push_field(&mut body, response.value.as_bytes())?;
+    body.extend_from_slice(if response.success {
+        &TRAILER_SUCCESS
+    } else {
+        &TRAILER_FAILURE
+    });
+    frame(response.msg_id, body)
+}
+
+/// Decodes a complete request frame, as received by a server.
+///
+/// # Returns
+///
+/// Returns the parsed `Request`.
+/// Returns `Error::ProtocolViolation` if the frame is truncated or malformed.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::codec::{self, Request};
+/// let request = Request::write(3, "$OV_PRO", "50");
+/// let frame = codec::encode(&request).unwrap();
+/// assert_eq!(codec::decode_request(&frame).unwrap(), request);
+/// ```
+pub fn decode_request(frame: &[u8]) -> Result<Request> {
+    let (msg_id, body) = split_frame(frame)?;
+    let mode = Mode::from_byte(
+        *body
+            .first()
+            .ok_or_else(|| invalid_data("Invalid request length"))?,
+    )?;
+    let (name, rest) = take_field(&body[1..])?;
+    let value = match mode {
+        Mode::Read => &[][..],
+        Mode::Write => take_field(rest)?.0,
+    };
+    Ok(Request {
+        msg_id,
+        mode,
+        name: String::from_utf8_lossy(name).to_string(),
+        value: String::from_utf8_lossy(value).to_string(),
+    })
+}
+
+/// Prepends the header to a message body.
+fn frame(msg_id: u16, body: Vec<u8>) -> Result<Vec<u8>> {
+    let body_len = length_field(body.len())?;
+    let mut frame = Vec::with_capacity(HEADER_LEN + body.len());
+    frame.extend_from_slice(&msg_id.to_be_bytes());
+    frame.extend_from_slice(&body_len);
+    frame.extend(body);
+    Ok(frame)
+}
+
+/// Splits a frame into its message ID and a body of the announced length.
+fn split_frame(frame: &[u8]) -> Result<(u16, &[u8])> {
+    let (msg_id, body_len) = header(frame).ok_or_else(|| invalid_data("Invalid frame length"))?;
+    let body = frame
+        .get(HEADER_LEN..HEADER_LEN + body_len)
+        .ok_or_else(|| invalid_data("Frame length does not match header"))?;
+    Ok((msg_id, body))
+}
+
+/// Appends a length-prefixed field to a message body.
+fn push_field(body: &mut Vec<u8>, field: &[u8]) -> Result<()> {
+    body.extend_from_slice(&length_field(field.len())?);
+    body.extend_from_slice(field);
+    Ok(())
+}
+
+/// Encodes a length as a 2-byte length field.
+fn length_field(len: usize) -> Result<[u8; 2]> {
+    u16::try_from(len)
+        .map(u16::to_be_bytes)
+        .map_err(|_| Error::ValueTooLong {
+            len,
+            max: MAX_BODY_LEN,
+        })
+}
+
+/// Reads a length-prefixed field and returns it together with the remaining bytes.
+fn take_field(buf: &[u8]) -> Result<(&[u8], &[u8])> {
+    if buf.len() < 2 {
+        return Err(invalid_data("Invalid field length"));
+    }
+    let len = u16::from_be_bytes([buf[0], buf[1]]) as usize;
+    if buf.len() < 2 + len {
+        return Err(invalid_data("Field length does not match message length"));
+    }
+    Ok((&buf[2..2 + len], &buf[2 + len..]))
+}
+
+/// Builds a `ProtocolViolation` error with the given message.
+fn invalid_data(msg: &'static str) -> Error {
+    Error::ProtocolViolation(msg.to_string())
+}
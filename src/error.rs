@@ -0,0 +1,147 @@
+use std::fmt;
+
+use crate::krl::ParseError;
+
+/// Errors returned by `OpenShowVar` and the protocol codec.
+#[derive(Debug)]
+pub enum Error {
+    /// The operation needs a connection, but none is established.
+    NotConnected,
+    /// The server did not answer within the configured timeout.
+    Timeout,
+    /// The server could not read the variable.
+    VariableNotFound {
+        /// Name of the variable that was read.
+        name: String,
+    },
+    /// The variable is read-only, so the write was not sent.
+    ReadOnly {
+        /// Name of the variable that was written.
+        name: String,
+    },
+    /// The server did not accept the written value.
+    WriteRejected {
+        /// Name of the variable that was written.
+        name: String,
+        /// Value that was rejected.
+        value: String,
+    },
+    /// A received message does not follow the KukaVarProxy protocol.
+    ProtocolViolation(String),
+    /// A variable name cannot be sent to the controller.
+    InvalidName {
+        /// The rejected variable name.
+        name: String,
+        /// Why the name was rejected.
+        reason: &'static str,
+    },
+    /// A value is longer than the protocol allows.
+    ValueTooLong {
+        /// Length of the rejected value in bytes.
+        len: usize,
+        /// Maximum allowed length in bytes.
+        max: usize,
+    },
+    /// An argument is invalid, such as an empty variable name.
+    InvalidInput(&'static str),
+    /// A value is not a valid KRL literal.
+    Parse(ParseError),
+    /// A KRL value cannot be converted to the requested type.
+    UnexpectedType {
+        /// Name of the requested type.
+        expected: &'static str,
+        /// The value that was found, as a KRL literal.
+        found: String,
+    },
+    /// A value has no KRL representation, or cannot be converted by a `serde` implementation.
+    Conversion(String),
+    /// A structure lacks a member that is required for the requested type.
+    MissingMember {
+        /// Name of the missing member.
+        name: String,
+    },
+    /// An I/O error occurred on the connection.
+    Io(std::io::Error),
+}
+
+/// A specialized `Result` type for OpenShowVar operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotConnected => write!(f, "Not connected"),
+            Error::Timeout => write!(f, "Timed out waiting for the server"),
+            Error::VariableNotFound { name } => write!(f, "Variable not found: {}", name),
+            Error::ReadOnly { name } => write!(f, "Variable is read-only: {}", name),
+            Error::WriteRejected { name, value } => {
+                write!(f, "Write rejected: {} = {}", name, value)
+            }
+            Error::ProtocolViolation(msg) => write!(f, "Protocol violation: {}", msg),
+            Error::InvalidName { name, reason } => {
+                write!(f, "Invalid variable name {:?}: {}", name, reason)
+            }
+            Error::ValueTooLong { len, max } => {
+                write!(f, "Value too long: {} bytes (maximum {})", len, max)
+            }
+            Error::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
+            Error::Parse(e) => write!(f, "Invalid KRL value: {}", e),
+            Error::UnexpectedType { expected, found } => {
+                write!(f, "Expected {}, found {}", expected, found)
+            }
+            Error::Conversion(msg) => write!(f, "Conversion failed: {}", msg),
+            Error::MissingMember { name } => write!(f, "Missing structure member: {}", name),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl Error {
+    /// Returns `true` if the error means the connection is lost or unusable,
+    /// so that reconnecting may allow the request to succeed.
+    ///
+    /// A `Timeout` is not a disconnect: the connection is kept, and a late
+    /// response is discarded by its message ID. A timeout that cuts off a
+    /// frame drops the connection, so the next request fails with `NotConnected`.
+    pub fn is_disconnect(&self) -> bool {
+        match self {
+            Error::NotConnected => true,
+            Error::Io(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::UnexpectedEof
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        // Socket timeouts surface as `WouldBlock` on Unix and `TimedOut` on Windows
+        match e.kind() {
+            std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => Error::Timeout,
+            std::io::ErrorKind::NotConnected => Error::NotConnected,
+            _ => Error::Io(e),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+/// Default variable read by the active health probe.
+pub(crate) const DEFAULT_PROBE_VARIABLE: &str = "$MODE_OP";
+
+/// Default time the active health probe waits for an answer.
+pub(crate) const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// State of the connection as determined by `OpenShowVar::health`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// No connection is established.
+    Disconnected,
+    /// The server answered the probe within the probe timeout.
+    Healthy {
+        /// Round-trip time of the probe request.
+        latency: Duration,
+    },
+    /// The connection is open, but the server did not answer the probe in time.
+    Unresponsive,
+    /// The peer closed the connection or the socket reported an error.
+    Broken,
+}
+
+impl ConnectionState {
+    /// Returns `true` if the server answered the probe.
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, ConnectionState::Healthy { .. })
+    }
+}
@@ -0,0 +1,201 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::OpenShowVar;
+
+/// Highest digital input and output number on a KRC4 (`$IN[1..4096]`, `$OUT[1..4096]`).
+pub const MAX_DIGITAL_IO: u32 = 4096;
+
+/// Highest analog input and output number on a KRC4 (`$ANIN[1..32]`, `$ANOUT[1..32]`).
+pub const MAX_ANALOG_IO: u32 = 32;
+
+/// The `Io` structure reads and writes the digital and analog I/O of the controller.
+///
+/// Digital I/O is read as `bool` and analog I/O as `f64` in the normalized
+/// range from -1.0 to 1.0, corresponding to -10 V to 10 V. Numbers are checked
+/// against the KRC4 limits `MAX_DIGITAL_IO` and `MAX_ANALOG_IO` before sending.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::OpenShowVar;
+/// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+/// match osv.io().digital_in(5) {
+///     Ok(state) => println!("Input 5: {}", state),
+///     Err(e) => println!("Reading error: {}", e),
+/// }
+/// ```
+pub struct Io<'a> {
+    /// Connection the I/O is accessed through.
+    osv: &'a mut OpenShowVar,
+}
+
+impl<'a> Io<'a> {
+    /// Creates the I/O facade on the given connection.
+    pub(crate) fn new(osv: &'a mut OpenShowVar) -> Io<'a> {
+        Io { osv }
+    }
+
+    /// Reads digital input `$IN[n]`.
+    pub fn digital_in(&mut self, n: u32) -> Result<bool> {
+        check_digital(n)?;
+        self.osv.read_as(&format!("$IN[{}]", n))
+    }
+
+    /// Reads the state of digital output `$OUT[n]`.
+    pub fn digital_out(&mut self, n: u32) -> Result<bool> {
+        check_digital(n)?;
+        self.osv.read_as(&format!("$OUT[{}]", n))
+    }
+
+    /// Sets digital output `$OUT[n]`.
+    pub fn set_digital_out(&mut self, n: u32, state: bool) -> Result<()> {
+        check_digital(n)?;
+        self.osv.write_value(&format!("$OUT[{}]", n), state)?;
+        Ok(())
+    }
+
+    /// Reads the digital inputs `$IN[first..=last]` in one pipelined batch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// match osv.io().digital_inputs(1, 16) {
+    ///     Ok(inputs) => println!("Inputs set: {:?}", inputs.ones().collect::<Vec<_>>()),
+    ///     Err(e) => println!("Reading error: {}", e),
+    /// }
+    /// ```
+    pub fn digital_inputs(&mut self, first: u32, last: u32) -> Result<BitSet> {
+        self.read_bits("$IN", first, last)
+    }
+
+    /// Reads the digital outputs `$OUT[first..=last]` in one pipelined batch.
+    pub fn digital_outputs(&mut self, first: u32, last: u32) -> Result<BitSet> {
+        self.read_bits("$OUT", first, last)
+    }
+
+    /// Sets consecutive digital outputs starting at `$OUT[first]` in one pipelined batch.
+    pub fn set_digital_outputs(&mut self, first: u32, states: &[bool]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+        let last = first.saturating_add(states.len() as u32).saturating_sub(1);
+        check_digital(first)?;
+        check_digital(last)?;
+        self.osv.write_array("$OUT", &[first..=last], states)
+    }
+
+    /// Sets digital output `$OUT[n]`, waits for `duration` and resets it.
+    ///
+    /// The call blocks for the duration of the pulse.
+    pub fn pulse_out(&mut self, n: u32, duration: Duration) -> Result<()> {
+        self.set_digital_out(n, true)?;
+        thread::sleep(duration);
+        self.set_digital_out(n, false)
+    }
+
+    /// Reads analog input `$ANIN[n]`, normalized to -1.0 to 1.0.
+    pub fn analog_in(&mut self, n: u32) -> Result<f64> {
+        check_analog(n)?;
+        self.osv.read_as(&format!("$ANIN[{}]", n))
+    }
+
+    /// Reads the value of analog output `$ANOUT[n]`, normalized to -1.0 to 1.0.
+    pub fn analog_out(&mut self, n: u32) -> Result<f64> {
+        check_analog(n)?;
+        self.osv.read_as(&format!("$ANOUT[{}]", n))
+    }
+
+    /// Sets analog output `$ANOUT[n]` to a value from -1.0 to 1.0.
+    pub fn set_analog_out(&mut self, n: u32, value: f64) -> Result<()> {
+        check_analog(n)?;
+        if !(-1.0..=1.0).contains(&value) {
+            return Err(Error::InvalidInput("Analog value must be from -1.0 to 1.0"));
+        }
+        self.osv.write_value(&format!("$ANOUT[{}]", n), value)?;
+        Ok(())
+    }
+
+    /// Reads a range of digital I/O of the given array.
+    fn read_bits(&mut self, base: &str, first: u32, last: u32) -> Result<BitSet> {
+        check_digital(first)?;
+        check_digital(last)?;
+        let bits = self.osv.read_array(base, &[first..=last])?;
+        Ok(BitSet { first, bits })
+    }
+}
+
+/// States of a range of consecutive digital inputs or outputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitSet {
+    /// Number of the first I/O in the range.
+    first: u32,
+    /// States in ascending I/O order.
+    bits: Vec<bool>,
+}
+
+impl BitSet {
+    /// Returns the state of I/O number `n`, or `None` if it is outside the range.
+    pub fn get(&self, n: u32) -> Option<bool> {
+        let index = n.checked_sub(self.first)?;
+        self.bits.get(index as usize).copied()
+    }
+
+    /// Returns the number of the first I/O in the range.
+    pub fn first(&self) -> u32 {
+        self.first
+    }
+
+    /// Returns the number of I/O in the range.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Returns `true` if the range is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Returns the number of I/O that are set.
+    pub fn count_ones(&self) -> usize {
+        self.bits.iter().filter(|bit| **bit).count()
+    }
+
+    /// Returns the numbers of the I/O that are set.
+    pub fn ones(&self) -> impl Iterator<Item = u32> + '_ {
+        self.iter().filter(|(_, bit)| *bit).map(|(n, _)| n)
+    }
+
+    /// Returns the I/O numbers and their states in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, bool)> + '_ {
+        (self.first..).zip(self.bits.iter().copied())
+    }
+
+    /// Returns the states in ascending I/O order.
+    pub fn as_slice(&self) -> &[bool] {
+        &self.bits
+    }
+}
+
+/// Checks that `n` is a valid digital I/O number.
+fn check_digital(n: u32) -> Result<()> {
+    if !(1..=MAX_DIGITAL_IO).contains(&n) {
+        return Err(Error::InvalidInput(
+            "Digital I/O number must be from 1 to 4096",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that `n` is a valid analog I/O number.
+fn check_analog(n: u32) -> Result<()> {
+    if !(1..=MAX_ANALOG_IO).contains(&n) {
+        return Err(Error::InvalidInput(
+            "Analog I/O number must be from 1 to 32",
+        ));
+    }
+    Ok(())
+}
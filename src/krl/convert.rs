@@ -0,0 +1,250 @@
+use crate::error::{Error, Result};
+use crate::krl::KrlValue;
+
+/// Conversion from a parsed KRL value into a Rust type.
+///
+/// Used by `OpenShowVar::read_as` to read typed values.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::{FromKrl, KrlValue};
+/// let value = KrlValue::parse("{X 1.0, Y 2.5}").unwrap();
+/// let (x, y) = <(f64, f64)>::from_krl(&value).unwrap();
+/// assert_eq!(y, 2.5);
+/// ```
+pub trait FromKrl: Sized {
+    /// Converts `value`, or returns `Error::UnexpectedType` if it has another type.
+    fn from_krl(value: &KrlValue) -> Result<Self>;
+
+    /// Returns the value to use when the variable or structure member does
+    /// not exist, or `None` if its absence is an error.
+    fn from_missing() -> Option<Self> {
+        None
+    }
+}
+
+/// Conversion from a Rust type into a KRL value.
+///
+/// Used by `OpenShowVar::write_value` to write typed values.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::{KrlValue, ToKrl};
+/// assert_eq!(true.to_krl_value().unwrap(), Some(KrlValue::Bool(true)));
+/// assert_eq!(None::<f64>.to_krl_value().unwrap(), None);
+/// assert!(i64::MAX.to_krl_value().is_err());
+/// ```
+pub trait ToKrl {
+    /// Converts the value, or returns `None` if there is nothing to write.
+    ///
+    /// Members that convert to `None` are left out of structures, which
+    /// makes optional members useful for partial writes.
+    ///
+    /// Returns `Error::Conversion` if the value has no KRL representation,
+    /// such as an integer outside the range of a KRL `INT`.
+    fn to_krl_value(&self) -> Result<Option<KrlValue>>;
+}
+
+impl KrlValue {
+    /// Converts the structure member with the given name.
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted member.
+    /// Returns `Error::MissingMember` if there is no such member and `T` has
+    /// no value for missing members, such as `None` for `Option`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::KrlValue;
+    /// let pos = KrlValue::parse("{POS: X 1.0, S 6}").unwrap();
+    /// assert_eq!(pos.get::<i32>("S").unwrap(), 6);
+    /// assert_eq!(pos.get::<Option<i32>>("T").unwrap(), None);
+    /// ```
+    pub fn get<T: FromKrl>(&self, name: &str) -> Result<T> {
+        match self.field(name) {
+            Some(value) => T::from_krl(value),
+            None => T::from_missing().ok_or_else(|| Error::MissingMember {
+                name: name.to_string(),
+            }),
+        }
+    }
+}
+
+/// Builds the error for a value that cannot be converted to `expected`.
+pub(crate) fn unexpected(expected: &'static str, value: &KrlValue) -> Error {
+    Error::UnexpectedType {
+        expected,
+        found: value.to_string(),
+    }
+}
+
+impl FromKrl for KrlValue {
+    fn from_krl(value: &KrlValue) -> Result<Self> {
+        Ok(value.clone())
+    }
+}
+
+impl ToKrl for KrlValue {
+    fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+        Ok(Some(self.clone()))
+    }
+}
+
+macro_rules! impl_integer {
+    ($($int:ty),*) => {
+        $(
+            impl FromKrl for $int {
+                fn from_krl(value: &KrlValue) -> Result<Self> {
+                    value
+                        .as_int()
+                        .and_then(|i| <$int>::try_from(i).ok())
+                        .ok_or_else(|| unexpected(stringify!($int), value))
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+macro_rules! impl_to_integer {
+    ($($int:ty),*) => {
+        $(
+            impl ToKrl for $int {
+                fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+                    // A KRL INT is a 32-bit signed integer
+                    i32::try_from(*self)
+                        .map(|i| Some(KrlValue::Int(i)))
+                        .map_err(|_| {
+                            Error::Conversion(format!("{} does not fit into a KRL INT", self))
+                        })
+                }
+            }
+        )*
+    };
+}
+
+impl_to_integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl FromKrl for f64 {
+    fn from_krl(value: &KrlValue) -> Result<Self> {
+        value.as_real().ok_or_else(|| unexpected("REAL", value))
+    }
+}
+
+impl ToKrl for f64 {
+    fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+        Ok(Some(KrlValue::from(*self)))
+    }
+}
+
+impl FromKrl for f32 {
+    fn from_krl(value: &KrlValue) -> Result<Self> {
+        f64::from_krl(value).map(|r| r as f32)
+    }
+}
+
+impl ToKrl for f32 {
+    fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+        Ok(Some(KrlValue::from(*self)))
+    }
+}
+
+impl FromKrl for bool {
+    fn from_krl(value: &KrlValue) -> Result<Self> {
+        value.as_bool().ok_or_else(|| unexpected("BOOL", value))
+    }
+}
+
+impl ToKrl for bool {
+    fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+        Ok(Some(KrlValue::from(*self)))
+    }
+}
+
+impl FromKrl for char {
+    fn from_krl(value: &KrlValue) -> Result<Self> {
+        value.as_char().ok_or_else(|| unexpected("CHAR", value))
+    }
+}
+
+impl ToKrl for char {
+    fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+        Ok(Some(KrlValue::from(*self)))
+    }
+}
+
+impl FromKrl for String {
+    fn from_krl(value: &KrlValue) -> Result<Self> {
+        match value {
+            KrlValue::String(s) => Ok(s.clone()),
+            KrlValue::Char(c) => Ok(c.to_string()),
+            _ => Err(unexpected("string", value)),
+        }
+    }
+}
+
+impl ToKrl for String {
+    fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+        Ok(Some(KrlValue::from(self.as_str())))
+    }
+}
+
+impl ToKrl for str {
+    fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+        Ok(Some(KrlValue::from(self)))
+    }
+}
+
+impl<T: ToKrl + ?Sized> ToKrl for &T {
+    fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+        (**self).to_krl_value()
+    }
+}
+
+impl<T: FromKrl> FromKrl for Option<T> {
+    fn from_krl(value: &KrlValue) -> Result<Self> {
+        T::from_krl(value).map(Some)
+    }
+
+    fn from_missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: ToKrl> ToKrl for Option<T> {
+    fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+        match self {
+            Some(value) => value.to_krl_value(),
+            None => Ok(None),
+        }
+    }
+}
+
+// Tuples are read from the members of a structure, in order
+macro_rules! impl_tuple {
+    ($len:literal, $expected:literal: $($name:ident),+) => {
+        impl<$($name: FromKrl),+> FromKrl for ($($name,)+) {
+            fn from_krl(value: &KrlValue) -> Result<Self> {
+                match value.fields() {
+                    Some(fields) if fields.len() == $len => {
+                        let mut members = fields.iter().map(|(_, member)| member);
+                        Ok(($($name::from_krl(members.next().unwrap())?,)+))
+                    }
+                    _ => Err(unexpected($expected, value)),
+                }
+            }
+        }
+    };
+}
+
+impl_tuple!(1, "structure with 1 member": A);
+impl_tuple!(2, "structure with 2 members": A, B);
+impl_tuple!(3, "structure with 3 members": A, B, C);
+impl_tuple!(4, "structure with 4 members": A, B, C, D);
+impl_tuple!(5, "structure with 5 members": A, B, C, D, E);
+impl_tuple!(6, "structure with 6 members": A, B, C, D, E, F);
@@ -0,0 +1,274 @@
+use ::serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use ::serde::forward_to_deserialize_any;
+
+use crate::error::{Error, Result};
+use crate::krl::convert::unexpected;
+use crate::krl::KrlValue;
+
+/// Deserializes a `T` from a KRL literal, such as a value returned by `OpenShowVar::read`.
+///
+/// Structure members are matched to struct fields case-insensitively, enum
+/// values to unit variants, and members missing for `Option` fields are `None`.
+///
+/// # Example
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Position {
+///     x: f64,
+///     y: f64,
+///     s: Option<i32>,
+/// }
+///
+/// let pos: Position = rs_openshowvar::krl::from_str("{POS: X 10.0, Y 20.0, Z 0.0}").unwrap();
+/// assert_eq!(pos.y, 20.0);
+/// assert_eq!(pos.s, None);
+/// ```
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T> {
+    from_value(&KrlValue::parse(s)?)
+}
+
+/// Deserializes a `T` from a parsed `KrlValue`.
+pub fn from_value<T: DeserializeOwned>(value: &KrlValue) -> Result<T> {
+    T::deserialize(value)
+}
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::Conversion(msg.to_string())
+    }
+}
+
+// Numeric targets go through `deserialize_number` instead of `deserialize_any`
+macro_rules! deserialize_number {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                deserialize_number(self, visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &KrlValue {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            KrlValue::Int(i) => visitor.visit_i32(*i),
+            KrlValue::Real(r) => visitor.visit_f64(*r),
+            KrlValue::Bool(b) => visitor.visit_bool(*b),
+            KrlValue::Char(c) => visitor.visit_char(*c),
+            KrlValue::String(s) | KrlValue::Enum(s) => visitor.visit_str(s),
+            KrlValue::Bitfield(bits) => visitor.visit_seq(Members {
+                values: bits
+                    .iter()
+                    .map(|bit| KrlValue::Bool(*bit))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            }),
+            KrlValue::Struct { fields, .. } => visitor.visit_map(Fields {
+                fields: fields.iter(),
+                names: &[],
+                value: None,
+            }),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        // KRL has no null, so a value that is present is always `Some`
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            // Tuples and sequences take the structure members in order
+            KrlValue::Struct { fields, .. } => visitor.visit_seq(Members {
+                values: fields
+                    .iter()
+                    .map(|(_, value)| value.clone())
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            }),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        names: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self {
+            KrlValue::Struct { fields, .. } => visitor.visit_map(Fields {
+                fields: fields.iter(),
+                names,
+                value: None,
+            }),
+            _ => Err(unexpected("structure", self)),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self {
+            KrlValue::Enum(name) | KrlValue::String(name) => {
+                // KRL names are case-insensitive
+                let variant = variants
+                    .iter()
+                    .find(|variant| variant.eq_ignore_ascii_case(name))
+                    .copied()
+                    .unwrap_or(name);
+                visitor.visit_enum(UnitVariant(variant))
+            }
+            _ => Err(unexpected("ENUM", self)),
+        }
+    }
+
+    deserialize_number! {
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64
+    }
+
+    forward_to_deserialize_any! {
+        bool char str string bytes byte_buf unit unit_struct map identifier ignored_any
+    }
+}
+
+/// Deserializes a number, reading bit patterns as the INT they stand for.
+fn deserialize_number<'de, V: Visitor<'de>>(value: &KrlValue, visitor: V) -> Result<V::Value> {
+    match value {
+        KrlValue::Bitfield(_) => match value.as_int() {
+            Some(i) => visitor.visit_i32(i),
+            None => Err(unexpected("INT", value)),
+        },
+        _ => de::Deserializer::deserialize_any(value, visitor),
+    }
+}
+
+/// Values handed out one after another as a sequence.
+struct Members {
+    /// Values not yet deserialized.
+    values: std::vec::IntoIter<KrlValue>,
+}
+
+impl<'de> SeqAccess<'de> for Members {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self.values
+            .next()
+            .map(|value| seed.deserialize(&value))
+            .transpose()
+    }
+}
+
+/// Structure members handed out as a map.
+struct Fields<'a> {
+    /// Members not yet deserialized.
+    fields: std::slice::Iter<'a, (String, KrlValue)>,
+    /// Field names of the target struct, matched case-insensitively.
+    names: &'static [&'static str],
+    /// Value of the member whose key was just returned.
+    value: Option<&'a KrlValue>,
+}
+
+impl<'de> MapAccess<'de> for Fields<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        let Some((member, value)) = self.fields.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        let key = self
+            .names
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(member))
+            .copied()
+            .unwrap_or(member);
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(Error::Conversion("Value requested before key".to_string())),
+        }
+    }
+}
+
+/// An enum value, deserialized as a unit variant.
+struct UnitVariant<'a>(&'a str);
+
+impl<'de> EnumAccess<'de> for UnitVariant<'_> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let variant = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(self.0))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for UnitVariant<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, _seed: T) -> Result<T::Value> {
+        Err(Error::Conversion(
+            "KRL enum values cannot hold data".to_string(),
+        ))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value> {
+        Err(Error::Conversion(
+            "KRL enum values cannot hold data".to_string(),
+        ))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value> {
+        Err(Error::Conversion(
+            "KRL enum values cannot hold data".to_string(),
+        ))
+    }
+}
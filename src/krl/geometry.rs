@@ -0,0 +1,247 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::krl::convert::unexpected;
+use crate::krl::{FromKrl, KrlValue, ToKrl};
+
+// Declares a struct mirroring a standard KRL structure, with its conversions
+// from and to `KrlValue` and the KRL literal syntax.
+macro_rules! krl_struct {
+    (
+        $(#[$meta:meta])*
+        $name:ident => $krl_type:literal {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident: $ty:ty => $member:literal,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Default)]
+        pub struct $name {
+            $(
+                $(#[$field_meta])*
+                pub $field: $ty,
+            )*
+        }
+
+        impl FromKrl for $name {
+            fn from_krl(value: &KrlValue) -> Result<Self> {
+                if value.fields().is_none() {
+                    return Err(unexpected($krl_type, value));
+                }
+                Ok($name {
+                    $($field: value.get($member)?,)*
+                })
+            }
+        }
+
+        impl ToKrl for $name {
+            fn to_krl_value(&self) -> Result<Option<KrlValue>> {
+                let mut fields = Vec::new();
+                $(
+                    if let Some(value) = self.$field.to_krl_value()? {
+                        fields.push(($member.to_string(), value));
+                    }
+                )*
+                Ok(Some(KrlValue::Struct {
+                    type_name: Some($krl_type.to_string()),
+                    fields,
+                }))
+            }
+        }
+
+        impl From<$name> for KrlValue {
+            fn from(value: $name) -> Self {
+                value
+                    .to_krl_value()
+                    .ok()
+                    .flatten()
+                    .expect("geometry types always convert")
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self> {
+                $name::from_krl(&KrlValue::parse(s)?)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                KrlValue::from(*self).fmt(f)
+            }
+        }
+    };
+}
+
+krl_struct! {
+    /// A Cartesian frame, KRL type `FRAME`, as used by `$TOOL` and `$BASE`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::krl::Frame;
+    /// let tool: Frame = "{FRAME: X 0.0, Y 0.0, Z 150.0, A 0.0, B 0.0, C 0.0}".parse().unwrap();
+    /// assert_eq!(tool.z, 150.0);
+    /// ```
+    Frame => "FRAME" {
+        /// Translation along X in mm.
+        x: f64 => "X",
+        /// Translation along Y in mm.
+        y: f64 => "Y",
+        /// Translation along Z in mm.
+        z: f64 => "Z",
+        /// Rotation about Z in degrees.
+        a: f64 => "A",
+        /// Rotation about Y in degrees.
+        b: f64 => "B",
+        /// Rotation about X in degrees.
+        c: f64 => "C",
+    }
+}
+
+krl_struct! {
+    /// A Cartesian position with posture, KRL type `POS`.
+    ///
+    /// `S` (status) and `T` (turn) are optional in KRL. When they are `None`
+    /// they are left out of written literals and the controller keeps its own.
+    Pos => "POS" {
+        /// Translation along X in mm.
+        x: f64 => "X",
+        /// Translation along Y in mm.
+        y: f64 => "Y",
+        /// Translation along Z in mm.
+        z: f64 => "Z",
+        /// Rotation about Z in degrees.
+        a: f64 => "A",
+        /// Rotation about Y in degrees.
+        b: f64 => "B",
+        /// Rotation about X in degrees.
+        c: f64 => "C",
+        /// Status bits selecting the axis configuration.
+        s: Option<i32> => "S",
+        /// Turn bits selecting the sign of the axis angles.
+        t: Option<i32> => "T",
+    }
+}
+
+krl_struct! {
+    /// A Cartesian position with posture and external axes, KRL type `E6POS`,
+    /// as returned for `$POS_ACT`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::krl::E6Pos;
+    /// let pos: E6Pos = "{E6POS: X 100.0, Y 0.0, Z 500.0, A 0.0, B 90.0, C 0.0, S 2, T 10, \
+    ///     E1 0.0, E2 0.0, E3 0.0, E4 0.0, E5 0.0, E6 0.0}".parse().unwrap();
+    /// assert_eq!(pos.b, 90.0);
+    /// assert_eq!(pos.s, Some(2));
+    /// ```
+    E6Pos => "E6POS" {
+        /// Translation along X in mm.
+        x: f64 => "X",
+        /// Translation along Y in mm.
+        y: f64 => "Y",
+        /// Translation along Z in mm.
+        z: f64 => "Z",
+        /// Rotation about Z in degrees.
+        a: f64 => "A",
+        /// Rotation about Y in degrees.
+        b: f64 => "B",
+        /// Rotation about X in degrees.
+        c: f64 => "C",
+        /// Status bits selecting the axis configuration.
+        s: Option<i32> => "S",
+        /// Turn bits selecting the sign of the axis angles.
+        t: Option<i32> => "T",
+        /// Position of external axis 1.
+        e1: f64 => "E1",
+        /// Position of external axis 2.
+        e2: f64 => "E2",
+        /// Position of external axis 3.
+        e3: f64 => "E3",
+        /// Position of external axis 4.
+        e4: f64 => "E4",
+        /// Position of external axis 5.
+        e5: f64 => "E5",
+        /// Position of external axis 6.
+        e6: f64 => "E6",
+    }
+}
+
+krl_struct! {
+    /// Robot axis angles, KRL type `AXIS`.
+    Axis => "AXIS" {
+        /// Angle of axis 1 in degrees.
+        a1: f64 => "A1",
+        /// Angle of axis 2 in degrees.
+        a2: f64 => "A2",
+        /// Angle of axis 3 in degrees.
+        a3: f64 => "A3",
+        /// Angle of axis 4 in degrees.
+        a4: f64 => "A4",
+        /// Angle of axis 5 in degrees.
+        a5: f64 => "A5",
+        /// Angle of axis 6 in degrees.
+        a6: f64 => "A6",
+    }
+}
+
+krl_struct! {
+    /// Robot and external axis positions, KRL type `E6AXIS`, as returned for `$AXIS_ACT`.
+    E6Axis => "E6AXIS" {
+        /// Angle of axis 1 in degrees.
+        a1: f64 => "A1",
+        /// Angle of axis 2 in degrees.
+        a2: f64 => "A2",
+        /// Angle of axis 3 in degrees.
+        a3: f64 => "A3",
+        /// Angle of axis 4 in degrees.
+        a4: f64 => "A4",
+        /// Angle of axis 5 in degrees.
+        a5: f64 => "A5",
+        /// Angle of axis 6 in degrees.
+        a6: f64 => "A6",
+        /// Position of external axis 1.
+        e1: f64 => "E1",
+        /// Position of external axis 2.
+        e2: f64 => "E2",
+        /// Position of external axis 3.
+        e3: f64 => "E3",
+        /// Position of external axis 4.
+        e4: f64 => "E4",
+        /// Position of external axis 5.
+        e5: f64 => "E5",
+        /// Position of external axis 6.
+        e6: f64 => "E6",
+    }
+}
+
+krl_struct! {
+    /// Mass moments of inertia, KRL type `INERTIA`.
+    Inertia => "INERTIA" {
+        /// Moment of inertia about X in kg·m².
+        x: f64 => "X",
+        /// Moment of inertia about Y in kg·m².
+        y: f64 => "Y",
+        /// Moment of inertia about Z in kg·m².
+        z: f64 => "Z",
+    }
+}
+
+krl_struct! {
+    /// Payload data, KRL type `LOAD`, as used by `$LOAD` and `$TOOL_DATA`.
+    Load => "LOAD" {
+        /// Mass in kg.
+        m: f64 => "M",
+        /// Center of mass and orientation of the principal axes.
+        cm: Frame => "CM",
+        /// Moments of inertia about the principal axes.
+        j: Inertia => "J",
+    }
+}
@@ -0,0 +1,30 @@
+//! Parsing and formatting of KRL literals as returned by `OpenShowVar::read`.
+//!
+//! The controller answers reads with the KRL literal syntax of the variable,
+//! such as `50`, `TRUE`, `#T1`, `"Program"` or
+//! `{E6POS: X 100.0, Y 0.0, Z 500.0, A 0.0, B 90.0, C 0.0, S 2, T 10}`.
+//! `KrlValue::parse` turns these strings into a `KrlValue` tree, and
+//! `KrlValue::to_krl` formats a `KrlValue` back into a literal for `write`.
+//! The `FromKrl` and `ToKrl` traits convert between `KrlValue` and Rust types,
+//! including the standard KRL structures such as `E6Pos`, `E6Axis` and `Frame`.
+//! With the `serde` feature, `from_str` and `to_string` map any `Deserialize`
+//! or `Serialize` type to and from KRL literals.
+
+pub(crate) mod convert;
+#[cfg(feature = "serde")]
+mod de;
+mod geometry;
+mod parse;
+#[cfg(feature = "serde")]
+mod ser;
+mod serialize;
+mod value;
+
+pub use convert::{FromKrl, ToKrl};
+#[cfg(feature = "serde")]
+pub use de::{from_str, from_value};
+pub use geometry::{Axis, E6Axis, E6Pos, Frame, Inertia, Load, Pos};
+pub use parse::ParseError;
+#[cfg(feature = "serde")]
+pub use ser::{to_string, to_value};
+pub use value::KrlValue;
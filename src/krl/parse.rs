@@ -0,0 +1,298 @@
+use std::fmt;
+
+use crate::krl::KrlValue;
+
+/// Deepest structure nesting accepted by the parser.
+///
+/// Real KRL data types nest only a few levels deep; the limit keeps a hostile
+/// or corrupted response from overflowing the stack of the parsing thread.
+pub(crate) const MAX_NESTING: usize = 32;
+
+/// Error returned when a string is not a valid KRL literal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset in the input at which the error was detected.
+    pub position: usize,
+    /// What was wrong at that position.
+    pub reason: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.reason, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a complete KRL literal, allowing surrounding whitespace.
+pub(crate) fn parse(input: &str) -> Result<KrlValue, ParseError> {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < input.len() {
+        return Err(parser.error("Unexpected characters after value"));
+    }
+    Ok(value)
+}
+
+/// Recursive descent parser over the bytes of a literal.
+struct Parser<'a> {
+    /// The literal being parsed.
+    input: &'a str,
+    /// Byte offset of the next character.
+    pos: usize,
+    /// Number of structures currently being parsed.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// Parses one value of any type.
+    fn value(&mut self) -> Result<KrlValue, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.structure(),
+            Some(b'"') => self.string(),
+            Some(b'#') => self.enumeration(),
+            Some(b'\'') => self.bitfield(),
+            Some(b'0'..=b'9' | b'+' | b'-' | b'.') => self.number(),
+            Some(c) if is_identifier_start(c) => {
+                let start = self.pos;
+                let word = self.identifier()?;
+                if word.eq_ignore_ascii_case("TRUE") {
+                    Ok(KrlValue::Bool(true))
+                } else if word.eq_ignore_ascii_case("FALSE") {
+                    Ok(KrlValue::Bool(false))
+                } else {
+                    Err(ParseError {
+                        position: start,
+                        reason: "Unknown identifier, expected TRUE or FALSE",
+                    })
+                }
+            }
+            Some(_) => Err(self.error("Unexpected character")),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    /// Parses a structure, limiting how deeply structures may be nested.
+    fn structure(&mut self) -> Result<KrlValue, ParseError> {
+        if self.depth == MAX_NESTING {
+            return Err(self.error("Structures nested too deeply"));
+        }
+        self.depth += 1;
+        let value = self.structure_members();
+        self.depth -= 1;
+        value
+    }
+
+    /// Parses the members of a structure such as `{E6POS: X 1.0, Y 2.0}`.
+    fn structure_members(&mut self) -> Result<KrlValue, ParseError> {
+        self.expect(b'{', "Expected '{'")?;
+        self.skip_whitespace();
+        let mut type_name = None;
+        let mut fields = Vec::new();
+        if self.eat(b'}') {
+            return Ok(KrlValue::Struct { type_name, fields });
+        }
+
+        // A leading identifier followed by a colon names the structure type
+        let mut name = self.identifier()?;
+        self.skip_whitespace();
+        if self.eat(b':') {
+            type_name = Some(name);
+            self.skip_whitespace();
+            if self.eat(b'}') {
+                return Ok(KrlValue::Struct { type_name, fields });
+            }
+            name = self.identifier()?;
+        }
+
+        loop {
+            // Members holding strings are written as `NAME[] "text"`
+            self.skip_whitespace();
+            if self.eat(b'[') {
+                self.skip_whitespace();
+                self.expect(b']', "Expected ']'")?;
+            }
+            let value = self.value()?;
+            fields.push((name, value));
+
+            self.skip_whitespace();
+            if self.eat(b'}') {
+                return Ok(KrlValue::Struct { type_name, fields });
+            }
+            self.expect(b',', "Expected ',' or '}'")?;
+            self.skip_whitespace();
+            name = self.identifier()?;
+        }
+    }
+
+    /// Parses a double-quoted string.
+    fn string(&mut self) -> Result<KrlValue, ParseError> {
+        let start = self.pos;
+        self.expect(b'"', "Expected '\"'")?;
+        match self.input[self.pos..].find('"') {
+            Some(len) => {
+                let text = &self.input[self.pos..self.pos + len];
+                self.pos += len + 1;
+                Ok(KrlValue::String(text.to_string()))
+            }
+            None => Err(ParseError {
+                position: start,
+                reason: "Unterminated string",
+            }),
+        }
+    }
+
+    /// Parses an enumeration value such as `#T1`.
+    fn enumeration(&mut self) -> Result<KrlValue, ParseError> {
+        self.expect(b'#', "Expected '#'")?;
+        Ok(KrlValue::Enum(self.identifier()?))
+    }
+
+    /// Parses a bit pattern such as `'B0101'` or `'H1F'`.
+    fn bitfield(&mut self) -> Result<KrlValue, ParseError> {
+        let start = self.pos;
+        self.expect(b'\'', "Expected '''")?;
+        let radix_bits = match self.peek() {
+            Some(b'B' | b'b') => 1,
+            Some(b'H' | b'h') => 4,
+            _ => return Err(self.error("Expected 'B' or 'H' after '''")),
+        };
+        self.pos += 1;
+
+        let mut bits = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == b'\'' {
+                break;
+            }
+            let digit = match (c as char).to_digit(1 << radix_bits) {
+                Some(digit) => digit,
+                None if radix_bits == 1 => return Err(self.error("Invalid binary digit")),
+                None => return Err(self.error("Invalid hexadecimal digit")),
+            };
+            bits.extend((0..radix_bits).rev().map(|bit| digit & (1 << bit) != 0));
+            self.pos += 1;
+        }
+        if bits.is_empty() && self.peek() == Some(b'\'') {
+            return Err(self.error("Expected digits"));
+        }
+        if !self.eat(b'\'') {
+            return Err(ParseError {
+                position: start,
+                reason: "Unterminated bit pattern",
+            });
+        }
+        Ok(KrlValue::Bitfield(bits))
+    }
+
+    /// Parses an `INT` or `REAL` number.
+    fn number(&mut self) -> Result<KrlValue, ParseError> {
+        let start = self.pos;
+        let mut real = false;
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+        let int_digits = self.digits();
+        if self.eat(b'.') {
+            real = true;
+            if int_digits + self.digits() == 0 {
+                return Err(self.error("Expected digits"));
+            }
+        } else if int_digits == 0 {
+            return Err(self.error("Expected digits"));
+        }
+        if matches!(self.peek(), Some(b'E' | b'e')) {
+            real = true;
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if self.digits() == 0 {
+                return Err(self.error("Expected exponent digits"));
+            }
+        }
+
+        let text = &self.input[start..self.pos];
+        if real {
+            text.parse().map(KrlValue::Real).map_err(|_| ParseError {
+                position: start,
+                reason: "Invalid REAL",
+            })
+        } else {
+            text.parse().map(KrlValue::Int).map_err(|_| ParseError {
+                position: start,
+                reason: "INT out of range",
+            })
+        }
+    }
+
+    /// Parses an identifier such as a structure member name.
+    fn identifier(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(c) if is_identifier_start(c) => {}
+            _ => return Err(self.error("Expected identifier")),
+        }
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if is_identifier_start(c) || c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        Ok(self.input[start..self.pos].to_string())
+    }
+
+    /// Skips decimal digits and returns how many there were.
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    /// Skips spaces, tabs and line breaks.
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes `expected` if it is the next character.
+    fn eat(&mut self, expected: u8) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    /// Consumes `expected`, or fails with `reason` at the current position.
+    fn expect(&mut self, expected: u8, reason: &'static str) -> Result<(), ParseError> {
+        if self.eat(expected) {
+            return Ok(());
+        }
+        Err(self.error(reason))
+    }
+
+    /// Returns the next byte without consuming it.
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    /// Builds an error at the current position.
+    fn error(&self, reason: &'static str) -> ParseError {
+        ParseError {
+            position: self.pos,
+            reason,
+        }
+    }
+}
+
+/// Returns `true` if `c` may start a KRL identifier.
+fn is_identifier_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_' || c == b'$'
+}
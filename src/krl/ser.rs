@@ -0,0 +1,336 @@
+use ::serde::ser::{self, Impossible, Serialize};
+
+use crate::error::{Error, Result};
+use crate::krl::KrlValue;
+
+/// Serializes a value as a KRL literal that can be passed to `OpenShowVar::write`.
+///
+/// Structs become structures without a type name, so the controller checks
+/// the members against the type of the written variable. Fields that are
+/// `None` are left out, which makes `Option` fields useful for partial writes.
+/// Unit enum variants become enum values, and member names and enum values
+/// are written in upper case.
+///
+/// # Example
+///
+/// ```
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Offset {
+///     x: f64,
+///     z: Option<f64>,
+/// }
+///
+/// let text = rs_openshowvar::krl::to_string(&Offset { x: 10.0, z: None }).unwrap();
+/// assert_eq!(text, "{X 10.0}");
+/// ```
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    to_value(value)?.to_krl()
+}
+
+/// Serializes a value into a `KrlValue`.
+///
+/// # Returns
+///
+/// Returns the `KrlValue`.
+/// Returns `Error::InvalidInput` if the value is `None`, or `Error::Conversion`
+/// if it has no KRL representation, such as an integer beyond the range of `INT`.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<KrlValue> {
+    value
+        .serialize(Serializer)?
+        .ok_or(Error::InvalidInput("No value to write"))
+}
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::Conversion(msg.to_string())
+    }
+}
+
+/// Serializer producing a `KrlValue`, or `None` for values that are left out.
+struct Serializer;
+
+/// Converts an integer that may not fit into a KRL `INT`.
+fn int<T: TryInto<i32> + std::fmt::Display + Copy>(v: T) -> Result<Option<KrlValue>> {
+    v.try_into()
+        .map(|i| Some(KrlValue::Int(i)))
+        .map_err(|_| Error::Conversion(format!("{} does not fit into an INT", v)))
+}
+
+/// Error for Rust data that has no KRL literal syntax.
+fn unsupported(what: &str) -> Error {
+    Error::Conversion(format!("{} cannot be written as a KRL literal", what))
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Option<KrlValue>;
+    type Error = Error;
+    type SerializeSeq = Bits;
+    type SerializeTuple = Bits;
+    type SerializeTupleStruct = Impossible<Option<KrlValue>, Error>;
+    type SerializeTupleVariant = Impossible<Option<KrlValue>, Error>;
+    type SerializeMap = Members;
+    type SerializeStruct = Members;
+    type SerializeStructVariant = Impossible<Option<KrlValue>, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
+        Ok(Some(KrlValue::Bool(v)))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok> {
+        int(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok> {
+        int(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok> {
+        int(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok> {
+        int(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok> {
+        int(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok> {
+        int(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok> {
+        int(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
+        int(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
+        Ok(Some(KrlValue::from(v)))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
+        Ok(Some(KrlValue::Real(v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok> {
+        Ok(Some(KrlValue::Char(v)))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok> {
+        Ok(Some(KrlValue::String(v.to_string())))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok> {
+        Err(unsupported("A byte array"))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok> {
+        Err(unsupported("A unit value"))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
+        Err(unsupported("A unit struct"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok> {
+        Ok(Some(KrlValue::Enum(variant.to_ascii_uppercase())))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok> {
+        Err(unsupported("An enum variant with data"))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(Bits(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        Ok(Bits(Vec::with_capacity(len)))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported("A tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported("An enum variant with data"))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(Members {
+            fields: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        Ok(Members {
+            fields: Vec::with_capacity(len),
+            key: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(unsupported("An enum variant with data"))
+    }
+}
+
+/// Sequence of booleans, written as a bit pattern.
+///
+/// KRL has no array literals, so sequences of other values are rejected.
+/// Empty sequences are left out, since there is no empty bit pattern.
+struct Bits(Vec<bool>);
+
+impl ser::SerializeSeq for Bits {
+    type Ok = Option<KrlValue>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        match value.serialize(Serializer)? {
+            Some(KrlValue::Bool(bit)) => {
+                self.0.push(bit);
+                Ok(())
+            }
+            _ => Err(unsupported("A sequence of values other than bool")),
+        }
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        if self.0.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(KrlValue::Bitfield(self.0)))
+    }
+}
+
+impl ser::SerializeTuple for Bits {
+    type Ok = Option<KrlValue>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Members of a structure, collected from a struct or a map with string keys.
+struct Members {
+    /// Members serialized so far.
+    fields: Vec<(String, KrlValue)>,
+    /// Key of a map entry whose value has not been serialized yet.
+    key: Option<String>,
+}
+
+impl Members {
+    /// Adds a member unless its value is left out.
+    fn push<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<()> {
+        if let Some(value) = value.serialize(Serializer)? {
+            self.fields.push((name.to_ascii_uppercase(), value));
+        }
+        Ok(())
+    }
+
+    /// Finishes the structure.
+    fn finish(self) -> Result<Option<KrlValue>> {
+        Ok(Some(KrlValue::Struct {
+            type_name: None,
+            fields: self.fields,
+        }))
+    }
+}
+
+impl ser::SerializeStruct for Members {
+    type Ok = Option<KrlValue>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Members {
+    type Ok = Option<KrlValue>;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        match key.serialize(Serializer)? {
+            Some(KrlValue::String(key)) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            _ => Err(unsupported("A map key other than a string")),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::Conversion("Value serialized before key".to_string()))?;
+        self.push(&key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
@@ -0,0 +1,145 @@
+use std::fmt::{self, Write};
+
+use crate::error::{Error, Result};
+use crate::krl::KrlValue;
+
+impl KrlValue {
+    /// Formats the value as a KRL literal that can be passed to `OpenShowVar::write`.
+    ///
+    /// Unlike the `Display` output, the value is checked first, so that values
+    /// the controller would reject or misread are reported before sending.
+    ///
+    /// # Returns
+    ///
+    /// Returns the KRL literal.
+    /// Returns `Error::InvalidInput` if a string contains a double quote, a
+    /// REAL is not finite, a bit pattern is empty, or an enum, type or member
+    /// name is not a KRL identifier.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::KrlValue;
+    /// let tool = KrlValue::partial([("X", KrlValue::from(10.0)), ("Y", KrlValue::from(20))]);
+    /// assert_eq!(tool.to_krl().unwrap(), "{X 10.0, Y 20}");
+    /// assert!(KrlValue::from("say \"hi\"").to_krl().is_err());
+    /// ```
+    pub fn to_krl(&self) -> Result<String> {
+        self.validate()?;
+        Ok(self.to_string())
+    }
+
+    /// Checks that the value can be written as a KRL literal.
+    fn validate(&self) -> Result<()> {
+        match self {
+            KrlValue::Int(_) | KrlValue::Bool(_) => Ok(()),
+            KrlValue::Real(r) if !r.is_finite() => Err(Error::InvalidInput("REAL is not finite")),
+            KrlValue::Real(_) => Ok(()),
+            KrlValue::Char('"') => Err(Error::InvalidInput("CHAR cannot be a double quote")),
+            KrlValue::Char(c) if !is_printable(*c) => {
+                Err(Error::InvalidInput("CHAR is not printable ASCII"))
+            }
+            KrlValue::Char(_) => Ok(()),
+            KrlValue::String(s) if s.contains('"') => {
+                Err(Error::InvalidInput("String contains a double quote"))
+            }
+            KrlValue::String(s) if !s.chars().all(is_printable) => Err(Error::InvalidInput(
+                "String contains non-printable characters",
+            )),
+            KrlValue::String(_) => Ok(()),
+            KrlValue::Enum(name) => validate_identifier(name, "Invalid ENUM value"),
+            KrlValue::Bitfield(bits) if bits.is_empty() => {
+                Err(Error::InvalidInput("Bit pattern is empty"))
+            }
+            KrlValue::Bitfield(_) => Ok(()),
+            KrlValue::Struct { type_name, fields } => {
+                if let Some(type_name) = type_name {
+                    validate_identifier(type_name, "Invalid structure type name")?;
+                }
+                for (name, value) in fields {
+                    validate_identifier(name, "Invalid structure member name")?;
+                    value.validate()?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Formats the value in canonical KRL literal syntax.
+///
+/// REALs always use `.` as decimal separator and contain a decimal point,
+/// strings are double-quoted, enum values carry their `#` prefix and string
+/// members of structures are written as `NAME[] "text"`.
+impl fmt::Display for KrlValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KrlValue::Int(i) => write!(f, "{}", i),
+            KrlValue::Real(r) => write_real(f, *r),
+            KrlValue::Bool(true) => f.write_str("TRUE"),
+            KrlValue::Bool(false) => f.write_str("FALSE"),
+            KrlValue::Char(c) => write!(f, "\"{}\"", c),
+            KrlValue::String(s) => write!(f, "\"{}\"", s),
+            KrlValue::Enum(name) => write!(f, "#{}", name),
+            KrlValue::Bitfield(bits) => {
+                f.write_str("'B")?;
+                for bit in bits {
+                    f.write_char(if *bit { '1' } else { '0' })?;
+                }
+                f.write_char('\'')
+            }
+            KrlValue::Struct { type_name, fields } => {
+                f.write_char('{')?;
+                if let Some(type_name) = type_name {
+                    write!(f, "{}: ", type_name)?;
+                }
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    match value {
+                        KrlValue::String(_) => write!(f, "{}[] {}", name, value)?,
+                        _ => write!(f, "{} {}", name, value)?,
+                    }
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+/// Writes a REAL with a decimal point, switching to an exponent for very large
+/// or very small magnitudes.
+fn write_real(f: &mut fmt::Formatter<'_>, r: f64) -> fmt::Result {
+    let magnitude = r.abs();
+    let text = if magnitude == 0.0 || (1e-5..1e15).contains(&magnitude) {
+        format!("{}", r)
+    } else {
+        format!("{:E}", r)
+    };
+    // KRL reads numbers without a decimal point or exponent as INT
+    match text.find('E') {
+        Some(exp) if !text[..exp].contains('.') => {
+            write!(f, "{}.0{}", &text[..exp], &text[exp..])
+        }
+        Some(_) => f.write_str(&text),
+        None if text.contains('.') => f.write_str(&text),
+        None => write!(f, "{}.0", text),
+    }
+}
+
+/// Returns `true` if `c` is a printable ASCII character.
+fn is_printable(c: char) -> bool {
+    c == ' ' || c.is_ascii_graphic()
+}
+
+/// Checks that `name` is a KRL identifier.
+fn validate_identifier(name: &str, reason: &'static str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid_start =
+        matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$');
+    if valid_start && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$') {
+        return Ok(());
+    }
+    Err(Error::InvalidInput(reason))
+}
@@ -0,0 +1,260 @@
+use std::str::FromStr;
+
+use crate::krl::parse::{self, ParseError};
+
+/// A KRL value, as read from or written to a variable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KrlValue {
+    /// An `INT` value, such as `42` or `-7`.
+    Int(i32),
+    /// A `REAL` value, such as `1.5` or `-2.0E-3`.
+    Real(f64),
+    /// A `BOOL` value, `TRUE` or `FALSE`.
+    Bool(bool),
+    /// A single `CHAR`.
+    ///
+    /// KRL writes characters and strings with the same double-quoted syntax,
+    /// so the parser always produces `String`. `as_char` accepts both.
+    Char(char),
+    /// A `CHAR` array holding text, such as `"Program"`.
+    String(String),
+    /// An `ENUM` value, stored without its `#` prefix.
+    Enum(String),
+    /// A `STRUC` value with its members in the order they were given.
+    Struct {
+        /// Structure type given before the colon, such as `E6POS`, if any.
+        type_name: Option<String>,
+        /// Member names and values.
+        fields: Vec<(String, KrlValue)>,
+    },
+    /// A bit pattern written as `'B0101'` or `'H1F'`, most significant bit first.
+    Bitfield(Vec<bool>),
+}
+
+impl KrlValue {
+    /// Parses a KRL literal.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Literal to parse, such as a value returned by `OpenShowVar::read`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the parsed `KrlValue`.
+    /// Returns `ParseError` with the byte position of the first invalid character.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::KrlValue;
+    /// let pos = KrlValue::parse("{POS: X 100.0, Y 0.0, Z 500.0}").unwrap();
+    /// assert_eq!(pos.field("X").and_then(KrlValue::as_real), Some(100.0));
+    /// ```
+    pub fn parse(input: &str) -> Result<KrlValue, ParseError> {
+        parse::parse(input)
+    }
+
+    /// Creates a structure without type name from the given members.
+    ///
+    /// Writing such a structure only changes the listed members of the
+    /// target variable and leaves the others untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::KrlValue;
+    /// let offset = KrlValue::partial([("Z", KrlValue::from(250.5))]);
+    /// assert_eq!(offset.to_string(), "{Z 250.5}");
+    /// ```
+    pub fn partial<N: Into<String>>(fields: impl IntoIterator<Item = (N, KrlValue)>) -> KrlValue {
+        KrlValue::Struct {
+            type_name: None,
+            fields: fields
+                .into_iter()
+                .map(|(name, value)| (name.into(), value))
+                .collect(),
+        }
+    }
+
+    /// Returns the value of an `INT`.
+    ///
+    /// Bit patterns such as `'B0101'` or `'H1F'` are `INT` literals in KRL, so a
+    /// `Bitfield` with at most 32 significant bits is accepted as well. The
+    /// highest of 32 bits is the sign bit, as in a KRL `INT`.
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            KrlValue::Int(i) => Some(*i),
+            KrlValue::Bitfield(bits) => {
+                let significant = bits
+                    .iter()
+                    .position(|&bit| bit)
+                    .map_or(&[][..], |first| &bits[first..]);
+                if significant.len() > 32 {
+                    return None;
+                }
+                let value = significant
+                    .iter()
+                    .fold(0u32, |value, &bit| (value << 1) | bit as u32);
+                Some(value as i32)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `REAL`, widening an `INT` or bit pattern if needed.
+    pub fn as_real(&self) -> Option<f64> {
+        match self {
+            KrlValue::Real(r) => Some(*r),
+            KrlValue::Int(_) | KrlValue::Bitfield(_) => self.as_int().map(f64::from),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `BOOL`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            KrlValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `CHAR`, or of a string holding exactly one character.
+    pub fn as_char(&self) -> Option<char> {
+        match self {
+            KrlValue::Char(c) => Some(*c),
+            KrlValue::String(s) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(c),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the text of a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            KrlValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of an `ENUM` value, without the `#` prefix.
+    pub fn as_enum(&self) -> Option<&str> {
+        match self {
+            KrlValue::Enum(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Returns the bits of a bit pattern, most significant bit first.
+    pub fn as_bits(&self) -> Option<&[bool]> {
+        match self {
+            KrlValue::Bitfield(bits) => Some(bits),
+            _ => None,
+        }
+    }
+
+    /// Returns the members of a structure.
+    pub fn fields(&self) -> Option<&[(String, KrlValue)]> {
+        match self {
+            KrlValue::Struct { fields, .. } => Some(fields),
+            _ => None,
+        }
+    }
+
+    /// Returns the structure member with the given name.
+    ///
+    /// Names are compared case-insensitively, as in KRL.
+    pub fn field(&self, name: &str) -> Option<&KrlValue> {
+        self.fields()?
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the type name of a structure, if the literal included one.
+    pub fn type_name(&self) -> Option<&str> {
+        match self {
+            KrlValue::Struct { type_name, .. } => type_name.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for KrlValue {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<KrlValue, ParseError> {
+        KrlValue::parse(s)
+    }
+}
+
+impl From<i32> for KrlValue {
+    fn from(i: i32) -> Self {
+        KrlValue::Int(i)
+    }
+}
+
+impl From<i16> for KrlValue {
+    fn from(i: i16) -> Self {
+        KrlValue::Int(i.into())
+    }
+}
+
+impl From<u16> for KrlValue {
+    fn from(i: u16) -> Self {
+        KrlValue::Int(i.into())
+    }
+}
+
+impl From<i8> for KrlValue {
+    fn from(i: i8) -> Self {
+        KrlValue::Int(i.into())
+    }
+}
+
+impl From<u8> for KrlValue {
+    fn from(i: u8) -> Self {
+        KrlValue::Int(i.into())
+    }
+}
+
+impl From<f64> for KrlValue {
+    fn from(r: f64) -> Self {
+        KrlValue::Real(r)
+    }
+}
+
+impl From<f32> for KrlValue {
+    fn from(r: f32) -> Self {
+        // Go through the shortest decimal form so that 0.1f32 stays 0.1
+        KrlValue::Real(r.to_string().parse().unwrap_or(r.into()))
+    }
+}
+
+impl From<bool> for KrlValue {
+    fn from(b: bool) -> Self {
+        KrlValue::Bool(b)
+    }
+}
+
+impl From<char> for KrlValue {
+    fn from(c: char) -> Self {
+        KrlValue::Char(c)
+    }
+}
+
+impl From<&str> for KrlValue {
+    fn from(s: &str) -> Self {
+        KrlValue::String(s.to_string())
+    }
+}
+
+impl From<String> for KrlValue {
+    fn from(s: String) -> Self {
+        KrlValue::String(s)
+    }
+}
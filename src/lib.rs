@@ -1,3 +1,38 @@
+mod array;
+#[cfg(feature = "tokio")]
+mod async_client;
+mod builder;
+pub mod codec;
+mod error;
+mod health;
+mod io;
+pub mod krl;
+mod pipeline;
+mod pool;
+mod reconnect;
 mod rs_openshowvar;
+mod sampler;
+mod shared;
+mod snapshot;
+mod subscription;
+mod system_var;
 
+pub use array::{MAX_ARRAY_DIMENSIONS, MAX_ARRAY_ELEMENTS, MAX_ARRAY_INDEX};
+#[cfg(feature = "tokio")]
+pub use async_client::AsyncOpenShowVar;
+pub use builder::{OpenShowVarBuilder, DEFAULT_MAX_FRAME_SIZE};
+pub use error::{Error, Result};
+pub use health::ConnectionState;
+pub use io::{BitSet, Io, MAX_ANALOG_IO, MAX_DIGITAL_IO};
+pub use krl::{FromKrl, KrlValue, ToKrl};
+pub use pipeline::Pipeline;
+pub use pool::{OpenShowVarPool, PooledConnection};
+pub use reconnect::ReconnectPolicy;
 pub use rs_openshowvar::OpenShowVar;
+#[cfg(feature = "derive")]
+pub use rs_openshowvar_derive::{KrlEnum, KrlStruct};
+pub use sampler::{Histogram, Sample, Sampler, SamplerStats};
+pub use shared::SharedOpenShowVar;
+pub use snapshot::{OperatingMode, ProgramState, RobotState};
+pub use subscription::{ChangeEvent, Subscription, Subscriptions, Watch};
+pub use system_var::{Access, SystemVar, SystemVarInfo};
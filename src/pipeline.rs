@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use crate::codec::{self, Mode, Request};
+use crate::error::{Error, Result};
+use crate::rs_openshowvar::response_value;
+use crate::OpenShowVar;
+
+/// Maximum number of requests in flight before waiting for their responses.
+///
+/// Bounding the window keeps both socket buffers from filling up while the
+/// server is still answering earlier requests.
+const PIPELINE_WINDOW: usize = 64;
+
+/// The `Pipeline` structure collects read and write requests and sends them back-to-back over one connection.
+///
+/// Each request is tagged with its own message ID, so responses are matched to
+/// their request regardless of the order in which they arrive.
+pub struct Pipeline<'a> {
+    /// Connection the requests are sent over.
+    osv: &'a mut OpenShowVar,
+    /// Queued requests as mode, variable name and value (empty for reads).
+    requests: Vec<(Mode, String, String)>,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Creates an empty pipeline on the given connection.
+    pub(crate) fn new(osv: &'a mut OpenShowVar) -> Pipeline<'a> {
+        Pipeline {
+            osv,
+            requests: Vec::new(),
+        }
+    }
+
+    /// Queues a read of the specified variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name` - Name of the variable to read.
+    pub fn read(&mut self, var_name: &str) -> &mut Self {
+        self.requests
+            .push((Mode::Read, var_name.to_string(), String::new()));
+        self
+    }
+
+    /// Queues a write of a value to the specified variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name` - Name of the variable to write.
+    /// * `val` - Value to write to the variable.
+    pub fn write(&mut self, var_name: &str, val: &str) -> &mut Self {
+        self.requests
+            .push((Mode::Write, var_name.to_string(), val.to_string()));
+        self
+    }
+
+    /// Returns the number of queued requests.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Returns `true` if no requests are queued.
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Sends all queued requests and waits for their responses.
+    ///
+    /// # Returns
+    ///
+    /// Returns one result per queued request, in the order they were queued.
+    /// Each result holds the variable value or the error reported for that request.
+    /// Returns `Error` if the connection fails before all responses arrive.
+    ///
+    /// If a `ReconnectPolicy` is configured, a pipeline of reads that fails
+    /// because the connection was lost is sent again after reconnecting.
+    /// Pipelines containing writes are only retried if the policy allows
+    /// retrying writes.
+    pub fn execute(self) -> Result<Vec<Result<String>>> {
+        // Pipelines made only of reads are retried after reconnects like `read`
+        let idempotent = self.requests.iter().all(|(mode, _, _)| *mode == Mode::Read);
+        let Pipeline { osv, requests } = self;
+        osv.with_reconnect(idempotent, |osv| send_requests(osv, &requests))
+    }
+}
+
+/// Sends the requests back-to-back and matches the responses by message ID.
+fn send_requests(
+    osv: &mut OpenShowVar,
+    requests: &[(Mode, String, String)],
+) -> Result<Vec<Result<String>>> {
+    let mut results: Vec<Option<Result<String>>> = (0..requests.len()).map(|_| None).collect();
+
+    for (window, batch) in requests.chunks(PIPELINE_WINDOW).enumerate() {
+        let offset = window * PIPELINE_WINDOW;
+        let mut pending = HashMap::new();
+        let mut frames = Vec::new();
+
+        // Reject empty names and values locally, exactly like `read` and `write` do
+        for (i, (mode, var_name, val)) in batch.iter().enumerate() {
+            if var_name.is_empty() {
+                results[offset + i] = Some(Err(Error::InvalidInput("Empty variable name")));
+                continue;
+            }
+            if *mode == Mode::Write && val.is_empty() {
+                results[offset + i] = Some(Err(Error::InvalidInput("Empty value")));
+                continue;
+            }
+            let msg_id = osv.take_msg_id();
+            let request = match mode {
+                Mode::Read => Request::read(msg_id, var_name),
+                Mode::Write => Request::write(msg_id, var_name, val),
+            };
+            // Invalid requests fail on their own without reaching the socket
+            match codec::encode(&request) {
+                Ok(frame) => {
+                    frames.extend(frame);
+                    pending.insert(msg_id, offset + i);
+                }
+                Err(e) => results[offset + i] = Some(Err(e)),
+            }
+        }
+
+        if pending.is_empty() {
+            continue;
+        }
+        osv.send_frames(&frames)?;
+
+        // Resolve each response by its message ID, whatever the arrival order
+        while !pending.is_empty() {
+            let frame = osv.receive_frame()?;
+            let (msg_id, _) = codec::header(&frame).expect("frame has a complete header");
+            match pending.remove(&msg_id) {
+                Some(index) => {
+                    let (_, var_name, val) = &requests[index];
+                    results[index] = Some(
+                        codec::decode(&frame)
+                            .and_then(|response| response_value(response, var_name, val)),
+                    );
+                }
+                None => osv.record_stale_response(),
+            }
+        }
+    }
+
+    Ok(results.into_iter().flatten().collect())
+}
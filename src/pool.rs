@@ -0,0 +1,309 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::builder::OpenShowVarBuilder;
+use crate::error::{Error, Result};
+use crate::OpenShowVar;
+
+/// Default time a connection may sit idle before it is probed on checkout.
+const DEFAULT_IDLE_CHECK: Duration = Duration::from_secs(30);
+
+/// Default time `get` waits for a connection to become available.
+const DEFAULT_CHECKOUT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The `OpenShowVarPool` structure keeps several connections to one controller.
+///
+/// KukaVarProxy serves several clients at once, so spreading requests over a
+/// few connections raises the achievable polling rate. Connections are opened
+/// on demand up to the pool size, using the settings of the given builder.
+/// A connection that has been idle longer than the idle check interval is
+/// probed with `OpenShowVar::health` before it is handed out, and connections
+/// that turn out to be broken are dropped and replaced by new ones.
+///
+/// The pool is `Sync`, so it can be shared between threads with `Arc`.
+///
+/// # Example
+///
+/// ```no_run
+/// use rs_openshowvar::{OpenShowVar, OpenShowVarPool};
+/// let pool = OpenShowVarPool::new(OpenShowVar::builder("192.168.1.10".to_string(), 7000), 4);
+/// let values = pool.read_many(&["$OV_PRO", "$MODE_OP", "$POS_ACT", "$AXIS_ACT"]).unwrap();
+/// println!("Read values: {:?}", values);
+/// ```
+pub struct OpenShowVarPool {
+    /// Settings used to open new connections.
+    builder: OpenShowVarBuilder,
+    /// Maximum number of open connections.
+    size: usize,
+    /// Idle time after which a connection is probed before being handed out.
+    idle_check: Duration,
+    /// Maximum time `get` waits for a connection to become available.
+    checkout_timeout: Duration,
+    /// Idle connections and the number of open connections.
+    state: Mutex<PoolState>,
+    /// Signaled whenever a connection is returned or closed.
+    available: Condvar,
+}
+
+/// Connections currently managed by the pool.
+struct PoolState {
+    /// Idle connections with the time they were returned to the pool.
+    idle: Vec<(OpenShowVar, Instant)>,
+    /// Number of open connections, both idle and checked out.
+    open: usize,
+}
+
+impl OpenShowVarPool {
+    /// Creates a pool holding up to `size` connections, without connecting yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - Settings used to open each connection.
+    /// * `size` - Maximum number of connections. A size of 0 is treated as 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::{OpenShowVar, OpenShowVarPool};
+    /// let pool = OpenShowVarPool::new(OpenShowVar::builder("127.0.0.1".to_string(), 7000), 4);
+    /// assert_eq!(pool.size(), 4);
+    /// ```
+    pub fn new(builder: OpenShowVarBuilder, size: usize) -> OpenShowVarPool {
+        OpenShowVarPool {
+            builder,
+            size: size.max(1),
+            idle_check: DEFAULT_IDLE_CHECK,
+            checkout_timeout: DEFAULT_CHECKOUT_TIMEOUT,
+            state: Mutex::new(PoolState {
+                idle: Vec::new(),
+                open: 0,
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Sets how long a connection may be idle before it is probed on checkout.
+    pub fn idle_check(mut self, idle: Duration) -> Self {
+        self.idle_check = idle;
+        self
+    }
+
+    /// Sets how long `get` waits for a connection when all of them are in use.
+    pub fn checkout_timeout(mut self, timeout: Duration) -> Self {
+        self.checkout_timeout = timeout;
+        self
+    }
+
+    /// Returns the maximum number of connections.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of open connections, both idle and checked out.
+    pub fn open_connections(&self) -> usize {
+        self.lock().open
+    }
+
+    /// Returns the number of connections waiting in the pool.
+    pub fn idle_connections(&self) -> usize {
+        self.lock().idle.len()
+    }
+
+    /// Opens connections until the pool is full.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` once every connection is open.
+    /// Returns `Error` if a connection fails; connections opened so far are kept.
+    pub fn connect(&self) -> Result<()> {
+        loop {
+            {
+                let mut state = self.lock();
+                if state.open >= self.size {
+                    return Ok(());
+                }
+                state.open += 1;
+            }
+            let osv = self.open()?;
+            self.lock().idle.push((osv, Instant::now()));
+            self.available.notify_one();
+        }
+    }
+
+    /// Checks out a connection, waiting while all connections are in use.
+    ///
+    /// Idle connections are reused before new ones are opened. The connection
+    /// returns to the pool when the `PooledConnection` is dropped, unless it
+    /// was closed in the meantime.
+    ///
+    /// # Returns
+    ///
+    /// Returns a connected `PooledConnection`.
+    /// Returns `Error::Timeout` if no connection becomes available within the
+    /// checkout timeout, or `Error` if a new connection cannot be opened.
+    pub fn get(&self) -> Result<PooledConnection<'_>> {
+        let deadline = Instant::now() + self.checkout_timeout;
+        let mut state = self.lock();
+        loop {
+            if let Some((mut osv, returned)) = state.idle.pop() {
+                if returned.elapsed() < self.idle_check {
+                    return Ok(PooledConnection::new(self, osv));
+                }
+                // Probe connections that may have gone stale while idle
+                drop(state);
+                if osv.health().is_healthy() {
+                    return Ok(PooledConnection::new(self, osv));
+                }
+                osv.disconnect();
+                state = self.lock();
+                state.open -= 1;
+                continue;
+            }
+
+            // Open a new connection outside the lock if the pool is not full
+            if state.open < self.size {
+                state.open += 1;
+                drop(state);
+                return self.open().map(|osv| PooledConnection::new(self, osv));
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout);
+            }
+            state = self
+                .available
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// Probes every idle connection and replaces the broken ones.
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of connections that were replaced.
+    /// Returns `Error` if a replacement connection cannot be opened.
+    pub fn check_idle(&self) -> Result<usize> {
+        let idle = std::mem::take(&mut self.lock().idle);
+        let mut replaced = 0;
+        for (mut osv, returned) in idle {
+            if osv.health().is_healthy() {
+                self.lock().idle.push((osv, returned));
+                continue;
+            }
+            osv.disconnect();
+            self.lock().open -= 1;
+            replaced += 1;
+        }
+        self.connect()?;
+        Ok(replaced)
+    }
+
+    /// Reads the specified variable on any available connection.
+    pub fn read(&self, var_name: &str) -> Result<String> {
+        self.get()?.read(var_name)
+    }
+
+    /// Writes a value to the specified variable on any available connection.
+    pub fn write(&self, var_name: &str, val: &str) -> Result<String> {
+        self.get()?.write(var_name, val)
+    }
+
+    /// Reads several variables, spreading them over the pool's connections.
+    ///
+    /// The variables are split into one pipelined batch per connection, and
+    /// the batches are sent in parallel.
+    ///
+    /// # Returns
+    ///
+    /// Returns one result per variable, in the order of `var_names`.
+    /// Returns `Error` if a connection fails during its batch.
+    pub fn read_many(&self, var_names: &[&str]) -> Result<Vec<Result<String>>> {
+        if var_names.is_empty() {
+            return Ok(Vec::new());
+        }
+        let batch_len = var_names.len().div_ceil(self.size);
+        thread::scope(|scope| {
+            let batches: Vec<_> = var_names
+                .chunks(batch_len)
+                .map(|batch| scope.spawn(move || self.get()?.read_many(batch)))
+                .collect();
+
+            let mut values = Vec::with_capacity(var_names.len());
+            for batch in batches {
+                values.extend(batch.join().expect("pool batch panicked")?);
+            }
+            Ok(values)
+        })
+    }
+
+    /// Opens a new connection, releasing its slot if that fails.
+    fn open(&self) -> Result<OpenShowVar> {
+        self.builder.clone().connect().inspect_err(|_| {
+            self.lock().open -= 1;
+            self.available.notify_one();
+        })
+    }
+
+    /// Locks the pool state, recovering it if another thread panicked.
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A connection checked out from an `OpenShowVarPool`.
+///
+/// It dereferences to `OpenShowVar` and returns to the pool when dropped.
+/// A connection that is no longer connected is closed instead, freeing its
+/// slot for a replacement.
+pub struct PooledConnection<'a> {
+    /// Pool the connection is returned to.
+    pool: &'a OpenShowVarPool,
+    /// The checked out connection, taken when it is returned.
+    osv: Option<OpenShowVar>,
+}
+
+impl<'a> PooledConnection<'a> {
+    /// Wraps a connection checked out from `pool`.
+    fn new(pool: &'a OpenShowVarPool, osv: OpenShowVar) -> PooledConnection<'a> {
+        PooledConnection {
+            pool,
+            osv: Some(osv),
+        }
+    }
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = OpenShowVar;
+
+    fn deref(&self) -> &OpenShowVar {
+        self.osv.as_ref().expect("connection already returned")
+    }
+}
+
+impl DerefMut for PooledConnection<'_> {
+    fn deref_mut(&mut self) -> &mut OpenShowVar {
+        self.osv.as_mut().expect("connection already returned")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(mut osv) = self.osv.take() {
+            let mut state = self.pool.lock();
+            // Broken connections are not returned, so a new one is opened later
+            if osv.is_connected() {
+                state.idle.push((osv, Instant::now()));
+            } else {
+                osv.disconnect();
+                state.open -= 1;
+            }
+            drop(state);
+            self.pool.available.notify_one();
+        }
+    }
+}
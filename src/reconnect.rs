@@ -0,0 +1,147 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The `ReconnectPolicy` structure describes how `OpenShowVar` heals a lost connection.
+///
+/// When a request fails because the connection dropped, or timed out partway
+/// through a frame, the client waits for an exponentially growing delay,
+/// reconnects and sends the request again, up to `max_attempts` times. A
+/// request that times out without a cut-off frame is returned to the caller
+/// as `Error::Timeout`, without reconnecting. Reads are always retried.
+/// Writes are only retried when enabled with `retry_writes`, since a write
+/// whose response was lost may already have been applied by the controller.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::{OpenShowVar, ReconnectPolicy};
+/// use std::time::Duration;
+/// let osv = OpenShowVar::builder("127.0.0.1".to_string(), 7000)
+///     .reconnect(
+///         ReconnectPolicy::new()
+///             .max_attempts(10)
+///             .backoff(Duration::from_millis(50), Duration::from_secs(2)),
+///     )
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    /// Maximum number of reconnect attempts for a single request.
+    max_attempts: u32,
+    /// Delay before the first reconnect attempt.
+    initial_backoff: Duration,
+    /// Upper bound for the delay between attempts.
+    max_backoff: Duration,
+    /// Factor by which the delay grows after each attempt.
+    multiplier: f64,
+    /// Whether delays are randomized to spread out reconnecting clients.
+    jitter: bool,
+    /// Whether writes are retried in addition to reads.
+    retry_writes: bool,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: true,
+            retry_writes: false,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Creates a policy with 5 attempts, a backoff from 100 ms doubling up to
+    /// 5 s with jitter, and read-only retries.
+    pub fn new() -> ReconnectPolicy {
+        ReconnectPolicy::default()
+    }
+
+    /// Sets the maximum number of reconnect attempts for a single request.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the delay before the first attempt and the upper bound for later delays.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Sets the factor by which the delay grows after each attempt.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Enables or disables randomization of the delays.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Enables or disables retrying writes after reconnecting.
+    pub fn retry_writes(mut self, retry_writes: bool) -> Self {
+        self.retry_writes = retry_writes;
+        self
+    }
+
+    /// Returns the maximum number of reconnect attempts for a single request.
+    pub(crate) fn attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns `true` if writes may be sent again after reconnecting.
+    pub(crate) fn retries_writes(&self) -> bool {
+        self.retry_writes
+    }
+
+    /// Returns a fresh sequence of delays for one retried request.
+    pub(crate) fn delays(&self) -> Backoff<'_> {
+        // Seed from the clock so concurrently reconnecting clients diverge
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Backoff {
+            policy: self,
+            attempt: 0,
+            state: seed | 1,
+        }
+    }
+}
+
+/// Sequence of delays between the reconnect attempts of one request.
+pub(crate) struct Backoff<'a> {
+    /// Policy the delays are derived from.
+    policy: &'a ReconnectPolicy,
+    /// Number of delays produced so far.
+    attempt: u32,
+    /// State of the xorshift generator used for jitter.
+    state: u64,
+}
+
+impl Iterator for Backoff<'_> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let base = self.policy.initial_backoff.as_secs_f64()
+            * self.policy.multiplier.powi(self.attempt as i32);
+        let base = base.min(self.policy.max_backoff.as_secs_f64()).max(0.0);
+        self.attempt += 1;
+
+        if !self.policy.jitter {
+            return Some(Duration::from_secs_f64(base));
+        }
+        // Keep half of the delay and randomize the other half
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        let random = (self.state >> 11) as f64 / (1u64 << 53) as f64;
+        Some(Duration::from_secs_f64(base / 2.0 + base / 2.0 * random))
+    }
+}
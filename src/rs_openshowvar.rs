@@ -1,6 +1,24 @@
 use std::io::{Read, Write};
-use std::net::TcpStream;
-use std::time::Duration;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::ops::RangeInclusive;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use socket2::{SockRef, TcpKeepalive};
+
+use crate::array;
+use crate::builder::{Config, OpenShowVarBuilder};
+use crate::codec::{self, Mode, Request, Response, HEADER_LEN};
+use crate::error::{Error, Result};
+use crate::health::ConnectionState;
+use crate::io::Io;
+use crate::krl::{FromKrl, KrlValue, ToKrl};
+use crate::pipeline::Pipeline;
+use crate::sampler::Sampler;
+use crate::shared::SharedOpenShowVar;
+use crate::snapshot::RobotState;
+use crate::subscription::Subscriptions;
+use crate::system_var::SystemVar;
 
 /// The `OpenShowVar` structure is used to connect to a robot control system and read/write variable values over a TCP connection.
 pub struct OpenShowVar {
@@ -10,6 +28,14 @@ pub struct OpenShowVar {
     tcp_port: u16,
     /// TCP connection.
     pub conn: Option<TcpStream>,
+    /// Timeouts, socket options and frame limits.
+    config: Config,
+    /// Message ID used for the next request on this connection.
+    next_msg_id: u16,
+    /// Number of responses discarded because their message ID did not match.
+    stale_responses: u64,
+    /// Time at which the last response was received on this connection.
+    last_exchange: Option<Instant>,
 }
 
 impl OpenShowVar {
@@ -31,19 +57,72 @@ impl OpenShowVar {
     /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
     /// ```
     pub fn new(tcp_ip: String, tcp_port: u16) -> OpenShowVar {
+        OpenShowVar::with_config(tcp_ip, tcp_port, Config::default())
+    }
+
+    /// Creates a builder for configuring timeouts and socket options before connecting.
+    ///
+    /// # Arguments
+    ///
+    /// * `tcp_ip` - IP address of the TCP server to connect to.
+    /// * `tcp_port` - Port number of the TCP server to connect to.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `OpenShowVarBuilder` with default settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// use std::time::Duration;
+    /// let osv = OpenShowVar::builder("127.0.0.1".to_string(), 7000)
+    ///     .read_timeout(Duration::from_millis(500))
+    ///     .build();
+    /// ```
+    pub fn builder(tcp_ip: String, tcp_port: u16) -> OpenShowVarBuilder {
+        OpenShowVarBuilder::new(tcp_ip, tcp_port)
+    }
+
+    /// Creates a new, unconnected instance using the given settings.
+    pub(crate) fn with_config(tcp_ip: String, tcp_port: u16, config: Config) -> OpenShowVar {
         OpenShowVar {
             tcp_ip,
             tcp_port,
             conn: None,
+            config,
+            next_msg_id: 0,
+            stale_responses: 0,
+            last_exchange: None,
         }
     }
 
+    /// Sets the maximum body length accepted for a received frame.
+    ///
+    /// Frames announcing a larger length in their header are discarded and
+    /// reported as an error instead of being buffered.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_frame_size` - Maximum number of body bytes in a single frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// osv.set_max_frame_size(4096);
+    /// ```
+    pub fn set_max_frame_size(&mut self, max_frame_size: usize) {
+        self.config.max_frame_size = max_frame_size;
+    }
+
     /// Connects to the TCP server.
     ///
     /// # Returns
     ///
     /// Returns `Ok(())` if the TCP connection is successful.
-    /// Returns `std::io::Error` if the TCP connection fails.
+    /// Returns `Error` if the TCP connection fails.
     ///
     /// # Example
     ///
@@ -55,41 +134,160 @@ impl OpenShowVar {
     ///     Err(e) => println!("Connection error: {}", e),
     /// }
     /// ```
-    pub fn connect(&mut self) -> std::io::Result<()> {
-        // Create address by combining IP address and port number
-        let addr = format!("{}:{}", self.tcp_ip, self.tcp_port);
-        // Establish TCP connection
-        let stream = TcpStream::connect(addr)?;
-        // Set a 5-second timeout for both reading and writing on the connection
-        stream.set_read_timeout(Some(Duration::new(5, 0)))?;
-        stream.set_write_timeout(Some(Duration::new(5, 0)))?;
+    pub fn connect(&mut self) -> Result<()> {
+        // Resolve the address and try each candidate within the connect timeout
+        let mut last_err = None;
+        let mut stream = None;
+        for addr in (self.tcp_ip.as_str(), self.tcp_port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, self.config.connect_timeout) {
+                Ok(s) => {
+                    stream = Some(s);
+                    break;
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        let stream = match (stream, last_err) {
+            (Some(stream), _) => stream,
+            (None, Some(e)) => return Err(e.into()),
+            (None, None) => return Err(Error::InvalidInput("Address did not resolve")),
+        };
+
+        // Apply the request timeouts and socket options to the connection
+        stream.set_read_timeout(Some(self.config.read_timeout))?;
+        stream.set_write_timeout(Some(self.config.write_timeout))?;
+        stream.set_nodelay(self.config.nodelay)?;
+        if let Some(idle) = self.config.keepalive {
+            SockRef::from(&stream).set_tcp_keepalive(&TcpKeepalive::new().with_time(idle))?;
+        }
         self.conn = Some(stream);
+        // Message IDs and exchange times are tracked per connection
+        self.next_msg_id = 0;
+        self.last_exchange = None;
         Ok(())
     }
 
+    /// Returns the number of responses discarded because their message ID
+    /// did not match the request that was waiting for an answer.
+    ///
+    /// Such responses are typically late replies to requests that timed out
+    /// earlier on the same connection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// assert_eq!(osv.stale_responses(), 0);
+    /// ```
+    pub fn stale_responses(&self) -> u64 {
+        self.stale_responses
+    }
+
     /// Checks if the connection to the TCP server is active.
     ///
+    /// This is a passive check that does not send anything: it reports `false`
+    /// if no connection exists or the peer has already closed it. Use `health`
+    /// to find out whether the server still answers requests.
+    ///
     /// # Returns
     ///
     /// Returns `true` if the connection is active, `false` otherwise.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use rs_openshowvar::OpenShowVar;
     /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
     /// osv.connect().unwrap();
     /// assert!(osv.is_connected());
     /// ```
     pub fn is_connected(&self) -> bool {
-        // Check if the connection object exists and the peer address is accessible
+        let conn = match self.conn {
+            Some(ref conn) => conn,
+            None => return false,
+        };
+
+        // A peer that closed the connection shows up as end of stream on a
+        // non-blocking peek; pending data or no data at all means it is open
+        if conn.set_nonblocking(true).is_err() {
+            return false;
+        }
+        let mut buf = [0u8; 1];
+        let alive = match conn.peek(&mut buf) {
+            Ok(0) => false,
+            Ok(_) => true,
+            Err(e) => e.kind() == std::io::ErrorKind::WouldBlock,
+        };
+
+        // Always restore blocking mode, so later requests keep their timeouts
+        let restored = conn.set_nonblocking(false).is_ok();
+        alive && restored
+    }
+
+    /// Actively checks whether the server still answers requests.
+    ///
+    /// A cheap variable (`$MODE_OP` by default) is read with a short timeout.
+    /// Any answer counts as healthy, even if the server reports a failure for
+    /// the probe variable.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `ConnectionState` determined by the probe.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::{ConnectionState, OpenShowVar};
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// assert_eq!(osv.health(), ConnectionState::Disconnected);
+    /// ```
+    pub fn health(&mut self) -> ConnectionState {
+        let conn = match self.conn {
+            Some(ref conn) => conn,
+            None => return ConnectionState::Disconnected,
+        };
+        if conn
+            .set_read_timeout(Some(self.config.probe_timeout))
+            .is_err()
+        {
+            return ConnectionState::Broken;
+        }
+
+        let start = Instant::now();
+        let probe_variable = self.config.probe_variable.clone();
+        let result = self.exchange(Mode::Read, &probe_variable, "");
+        let latency = start.elapsed();
+
+        // Restore the regular request timeout
         if let Some(ref conn) = self.conn {
-            conn.peer_addr().is_ok()
-        } else {
-            false
+            let _ = conn.set_read_timeout(Some(self.config.read_timeout));
+        }
+
+        match result {
+            Ok(_) => ConnectionState::Healthy { latency },
+            Err(Error::Timeout) => ConnectionState::Unresponsive,
+            Err(_) => ConnectionState::Broken,
         }
     }
 
+    /// Returns the time at which the last response was received on this connection.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if no response has been received since connecting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// assert!(osv.last_exchange().is_none());
+    /// ```
+    pub fn last_exchange(&self) -> Option<Instant> {
+        self.last_exchange
+    }
+
     /// Sends a variable value.
     ///
     /// # Arguments
@@ -99,7 +297,9 @@ impl OpenShowVar {
     ///
     /// # Returns
     ///
-    /// Returns a `std::io::Result` depending on the sent data.
+    /// Returns the raw response frame.
+    /// Returns `Error::VariableNotFound` if the server cannot read the variable,
+    /// or `Error::WriteRejected` if it does not accept the written value.
     ///
     /// # Example
     ///
@@ -111,79 +311,204 @@ impl OpenShowVar {
     ///     Err(e) => println!("Sending error: {}", e),
     /// }
     /// ```
-    pub fn send(&mut self, var_name: &str, val: &str) -> std::io::Result<Vec<u8>> {
-        // Calculate lengths of variable name and value
-        let mut msg = Vec::new();
-        let mut temp = Vec::new();
-
-        // If value exists, add its length and value
-        if !val.is_empty() {
-            let val_len = val.len();
-            msg.push(((val_len & 0xff00) >> 8) as u8);
-            msg.push((val_len & 0x00ff) as u8);
-            msg.extend_from_slice(val.as_bytes());
+    pub fn send(&mut self, var_name: &str, val: &str) -> Result<Vec<u8>> {
+        let mode = if val.is_empty() {
+            Mode::Read
+        } else {
+            Mode::Write
+        };
+        let response = self.transact(mode, var_name, val)?;
+
+        // Check the status trailer, reporting rejected writes apart from missing variables
+        response_value(codec::decode(&response)?, var_name, val)?;
+        Ok(response)
+    }
+
+    /// Sends a single request and returns the parsed response.
+    ///
+    /// Unlike `read` and `write`, a failure reported by the server is not turned
+    /// into an error; it is returned in the `success` field of the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - Whether the variable is read or written.
+    /// * `var_name` - Name of the variable.
+    /// * `val` - Value to write. Ignored for reads.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Response` received from the server.
+    /// Returns `Error` if the request cannot be sent or the response is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::codec::Mode;
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// match osv.exchange(Mode::Read, "$OV_PRO", "") {
+    ///     Ok(response) if response.success => println!("Value: {}", response.value),
+    ///     Ok(_) => println!("Request rejected"),
+    ///     Err(e) => println!("Exchange error: {}", e),
+    /// }
+    /// ```
+    pub fn exchange(&mut self, mode: Mode, var_name: &str, val: &str) -> Result<Response> {
+        let response = self.transact(mode, var_name, val)?;
+        codec::decode(&response)
+    }
+
+    /// Creates a pipeline that sends several requests back-to-back over this connection.
+    ///
+    /// # Returns
+    ///
+    /// Returns an empty `Pipeline` borrowing this connection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// let mut pipeline = osv.pipeline();
+    /// pipeline.read("$OV_PRO").read("$MODE_OP");
+    /// match pipeline.execute() {
+    ///     Ok(results) => println!("Results: {:?}", results),
+    ///     Err(e) => println!("Pipeline error: {}", e),
+    /// }
+    /// ```
+    pub fn pipeline(&mut self) -> Pipeline<'_> {
+        Pipeline::new(self)
+    }
+
+    /// Reads several variables in one pipelined batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_names` - Names of the variables to read.
+    ///
+    /// # Returns
+    ///
+    /// Returns one result per variable, in the order of `var_names`.
+    /// Returns `Error` if the connection fails during the batch.
+    ///
+    /// If a `ReconnectPolicy` is configured, a batch that fails because the
+    /// connection was lost is sent again after reconnecting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// match osv.read_many(&["$OV_PRO", "$MODE_OP"]) {
+    ///     Ok(values) => println!("Read values: {:?}", values),
+    ///     Err(e) => println!("Reading error: {}", e),
+    /// }
+    /// ```
+    pub fn read_many(&mut self, var_names: &[&str]) -> Result<Vec<Result<String>>> {
+        let mut pipeline = self.pipeline();
+        for var_name in var_names {
+            pipeline.read(var_name);
         }
+        pipeline.execute()
+    }
+
+    /// Runs a request and, if the connection was lost, reconnects and runs it
+    /// again according to the reconnect policy.
+    ///
+    /// Requests that are not `idempotent` are only retried if the policy allows
+    /// retrying writes.
+    pub(crate) fn with_reconnect<T>(
+        &mut self,
+        idempotent: bool,
+        mut request: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let mut result = request(self);
+        let policy = match self.config.reconnect.clone() {
+            Some(policy) if idempotent || policy.retries_writes() => policy,
+            _ => return result,
+        };
 
-        // If variable name exists, add its length and name
-        if !var_name.is_empty() {
-            let var_name_len = var_name.len();
-            temp.push(((var_name_len & 0xff00) >> 8) as u8);
-            temp.push((var_name_len & 0x00ff) as u8);
-            temp.extend_from_slice(var_name.as_bytes());
+        for delay in policy.delays().take(policy.attempts() as usize) {
+            match result {
+                Err(ref e) if e.is_disconnect() => {}
+                // A timeout only counts if it left a frame half read and the
+                // connection was dropped; otherwise the server is just slow
+                Err(Error::Timeout) if self.conn.is_none() => {}
+                _ => break,
+            }
+            thread::sleep(delay);
+            self.disconnect();
+            result = self.connect().and_then(|_| request(self));
         }
+        result
+    }
 
-        // If value exists, indicate its presence by adding a byte. Indicates whether it's Read or Write
-        if !val.is_empty() {
-            temp.insert(0, 1);
-        } else {
-            temp.insert(0, 0);
+    /// Sends one request and returns the raw frame answering it.
+    fn transact(&mut self, mode: Mode, var_name: &str, val: &str) -> Result<Vec<u8>> {
+        // Take the next message ID so the response can be matched to this request
+        let msg_id = self.take_msg_id();
+        let request = codec::encode(&Request {
+            msg_id,
+            mode,
+            name: var_name.to_string(),
+            value: val.to_string(),
+        })?;
+        self.send_frames(&request)?;
+
+        // Read frames until the one answering this request arrives.
+        // Replies carrying another ID belong to earlier, timed-out requests.
+        loop {
+            let frame = self.receive_frame()?;
+            if codec::header(&frame).map(|(id, _)| id) == Some(msg_id) {
+                return Ok(frame);
+            }
+            self.stale_responses += 1;
         }
+    }
 
-        msg = [&temp[..], &msg[..]].concat();
+    /// Returns the message ID for the next request and advances the counter.
+    pub(crate) fn take_msg_id(&mut self) -> u16 {
+        let msg_id = self.next_msg_id;
+        self.next_msg_id = self.next_msg_id.wrapping_add(1);
+        msg_id
+    }
 
-        // Calculate message length and create header
-        let msg_len = msg.len() as u16;
-        let mut header = vec![0u8; 4];
-        header[0] = ((0 & 0xff00) >> 8) as u8;
-        header[1] = (0 & 0x00ff) as u8;
-        header[2] = ((msg_len & 0xff00) >> 8) as u8;
-        header[3] = (msg_len & 0x00ff) as u8;
+    /// Counts a received frame that did not answer any pending request.
+    pub(crate) fn record_stale_response(&mut self) {
+        self.stale_responses += 1;
+    }
 
-        // Create request by combining header and message
-        let request = [&header[..], &msg[..]].concat();
+    /// Writes one or more encoded request frames to the connection.
+    ///
+    /// A failed or timed-out write drops the connection, since part of a
+    /// frame may already have been sent and the server would read the next
+    /// request out of step.
+    pub(crate) fn send_frames(&mut self, frames: &[u8]) -> Result<()> {
+        let conn = self.conn.as_mut().ok_or_else(not_connected)?;
+        if let Err(e) = conn.write_all(frames) {
+            self.conn = None;
+            return Err(e.into());
+        }
+        Ok(())
+    }
 
-        // If connection exists, send the request
-        if let Some(ref mut conn) = self.conn {
-            // Set a 2-second timeout for writing from the connection
-            conn.set_write_timeout(Some(Duration::new(2, 0)))?;
-            conn.write_all(&request)?;
-            let mut response = vec![0u8; 1024];
-            // Set a 2-second timeout for reading from the connection
-            conn.set_read_timeout(Some(Duration::new(2, 0)))?;
-            let n = conn.read(&mut response)?;
-            response.truncate(n);
-
-            // Filter visible characters and process the response
-            let visible_chars: Vec<u8> = response
-                .iter()
-                .cloned()
-                .filter(|&byte| byte >= 32 && byte <= 126)
-                .collect();
-
-            let response_str = String::from_utf8_lossy(&visible_chars).to_string();
-            // Check for error conditions
-            if response_str.trim().is_empty() || response[response.len() - 1] == 0 {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    "Variable not found",
-                ));
+    /// Reads the next complete response frame from the connection.
+    ///
+    /// A failure that leaves part of a frame unread, or a broken socket, drops
+    /// the connection, since the following frames can no longer be told apart.
+    pub(crate) fn receive_frame(&mut self) -> Result<Vec<u8>> {
+        let conn = self.conn.as_mut().ok_or_else(not_connected)?;
+        let mut partial = false;
+        match read_frame(conn, self.config.max_frame_size, &mut partial) {
+            Ok(frame) => {
+                self.last_exchange = Some(Instant::now());
+                Ok(frame)
+            }
+            Err(e) => {
+                if partial || matches!(e, Error::Io(_)) {
+                    self.conn = None;
+                }
+                Err(e)
             }
-            Ok(response)
-        } else {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::NotConnected,
-                "Not connected",
-            ))
         }
     }
 
@@ -195,7 +520,11 @@ impl OpenShowVar {
     ///
     /// # Returns
     ///
-    /// Returns the read variable value inside `std::io::Result<String>`.
+    /// Returns the read variable value inside `Result<String>`.
+    /// Returns `Error::VariableNotFound` if the server cannot read the variable.
+    ///
+    /// If a `ReconnectPolicy` is configured, a read that fails because the
+    /// connection was lost is retried after reconnecting.
     ///
     /// # Example
     ///
@@ -207,40 +536,19 @@ impl OpenShowVar {
     ///     Err(e) => println!("Reading error: {}", e),
     /// }
     /// ```
-    pub fn read(&mut self, var_name: &str) -> std::io::Result<String> {
+    pub fn read(&mut self, var_name: &str) -> Result<String> {
         // Return error if variable name to read is empty
         if var_name.is_empty() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Empty variable name",
-            ));
-        }
-
-        // Read variable value
-        let response = self.send(var_name, "")?;
-
-        // Check if the response is valid
-        if response.len() < 7 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Invalid response length",
-            ));
+            return Err(Error::InvalidInput("Empty variable name"));
         }
 
-        // Get value length
-        let val_len = ((response[5] as u16) << 8) | (response[6] as u16);
+        // Read variable value, retrying after reconnects if a policy is set
+        self.with_reconnect(true, |osv| {
+            let response = osv.exchange(Mode::Read, var_name, "")?;
 
-        // Return error if response length doesn't match value length
-        if response.len() < (7 + val_len as usize) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Response length does not match value length",
-            ));
-        }
-
-        // Return the variable value as a string
-        let var_value = String::from_utf8_lossy(&response[7..(7 + val_len as usize)]).to_string();
-        Ok(var_value)
+            // Return the variable value as a string
+            response_value(response, var_name, "")
+        })
     }
 
     /// Writes a value to the specified variable.
@@ -252,7 +560,11 @@ impl OpenShowVar {
     ///
     /// # Returns
     ///
-    /// Returns the written variable value inside `std::io::Result<String>`.
+    /// Returns the written variable value inside `Result<String>`.
+    /// Returns `Error::WriteRejected` if the server does not accept the value.
+    ///
+    /// A write that fails because the connection was lost is only retried if the
+    /// configured `ReconnectPolicy` allows retrying writes.
     ///
     /// # Example
     ///
@@ -264,48 +576,346 @@ impl OpenShowVar {
     ///     Err(e) => println!("Writing error: {}", e),
     /// }
     /// ```
-    pub fn write(&mut self, var_name: &str, val: &str) -> std::io::Result<String> {
+    pub fn write(&mut self, var_name: &str, val: &str) -> Result<String> {
         // Return error if variable name to write is empty
         if var_name.is_empty() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Empty variable name",
-            ));
+            return Err(Error::InvalidInput("Empty variable name"));
         }
 
         // Return error if value to write is empty
         if val.is_empty() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Empty value",
-            ));
+            return Err(Error::InvalidInput("Empty value"));
         }
 
-        // Write the variable value
-        let response = self.send(var_name, val)?;
+        // Write the variable value, retried only if the policy allows it
+        self.with_reconnect(false, |osv| {
+            let response = osv.exchange(Mode::Write, var_name, val)?;
 
-        // Check if the response is valid
-        if response.len() < 7 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Invalid response length",
-            ));
+            // Return the variable value as a string
+            response_value(response, var_name, val)
+        })
+    }
+
+    /// Reads the specified variable and converts it to `T`.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name` - Name of the variable to read.
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted variable value. If `T` is an `Option`, a variable
+    /// that does not exist is returned as `None`.
+    /// Returns `Error::Parse` if the value is not a KRL literal, or
+    /// `Error::UnexpectedType` if it cannot be converted to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// match osv.read_as::<f64>("$OV_PRO") {
+    ///     Ok(val) => println!("Override: {}", val),
+    ///     Err(e) => println!("Reading error: {}", e),
+    /// }
+    /// ```
+    pub fn read_as<T: FromKrl>(&mut self, var_name: &str) -> Result<T> {
+        match self.read(var_name) {
+            Ok(val) => T::from_krl(&KrlValue::parse(&val)?),
+            Err(Error::VariableNotFound { name }) => {
+                T::from_missing().ok_or(Error::VariableNotFound { name })
+            }
+            Err(e) => Err(e),
         }
+    }
 
-        // Get value length
-        let val_len = ((response[5] as u16) << 8) | (response[6] as u16);
+    /// Converts `val` to a KRL literal and writes it to the specified variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name` - Name of the variable to write.
+    /// * `val` - Value to write, such as a `bool`, `f64`, `&str` or `KrlValue`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the written KRL literal inside `Result<String>`.
+    /// Returns `Error::InvalidInput` if there is nothing to write, such as
+    /// `None`, or the value cannot be formatted as a KRL literal.
+    /// Returns `Error::Conversion` if the value has no KRL representation,
+    /// such as an integer outside the range of a KRL `INT`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// match osv.write_value("$OUT[3]", true) {
+    ///     Ok(val) => println!("Written value: {}", val),
+    ///     Err(e) => println!("Writing error: {}", e),
+    /// }
+    /// ```
+    pub fn write_value<T: ToKrl>(&mut self, var_name: &str, val: T) -> Result<String> {
+        let val = val
+            .to_krl_value()?
+            .ok_or(Error::InvalidInput("No value to write"))?
+            .to_krl()?;
+        self.write(var_name, &val)
+    }
 
-        // Return error if response length doesn't match value length
-        if response.len() < (7 + val_len as usize) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Response length does not match value length",
+    /// Reads the state of the robot in one pipelined batch.
+    ///
+    /// The operating mode, override, position, program state, drive and
+    /// safety signals and the active tool and base are requested back-to-back,
+    /// so all values come from as close to the same instant as the protocol
+    /// allows.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `RobotState` with the time the batch was sent. Optional
+    /// values that cannot be read or converted are `None`.
+    /// Returns `Error` if the connection fails, or if the mode, override,
+    /// position or program state cannot be read or converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// match osv.snapshot() {
+    ///     Ok(state) => println!("Mode {:?} at {}", state.mode_op, state.pos_act),
+    ///     Err(e) => println!("Reading error: {}", e),
+    /// }
+    /// ```
+    pub fn snapshot(&mut self) -> Result<RobotState> {
+        RobotState::read(self)
+    }
+
+    /// Reads a system variable from the catalog and converts it to `T`.
+    ///
+    /// # Arguments
+    ///
+    /// * `var` - Variable to read.
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted variable value.
+    /// Returns `Error::UnexpectedType` if the value cannot be converted to `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::krl::E6Pos;
+    /// use rs_openshowvar::{OpenShowVar, SystemVar};
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// match osv.read_system::<E6Pos>(SystemVar::PosAct) {
+    ///     Ok(pos) => println!("Position: {}", pos),
+    ///     Err(e) => println!("Reading error: {}", e),
+    /// }
+    /// ```
+    pub fn read_system<T: FromKrl>(&mut self, var: SystemVar) -> Result<T> {
+        self.read_as(var.name())
+    }
+
+    /// Writes a system variable from the catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `var` - Variable to write.
+    /// * `val` - Value to write.
+    ///
+    /// # Returns
+    ///
+    /// Returns the written KRL literal inside `Result<String>`.
+    /// Returns `Error::ReadOnly` without sending anything if the catalog marks
+    /// the variable as read-only.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::{Error, OpenShowVar, SystemVar};
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// let result = osv.write_system(SystemVar::ModeOp, "#AUT");
+    /// assert!(matches!(result, Err(Error::ReadOnly { .. })));
+    /// ```
+    pub fn write_system<T: ToKrl>(&mut self, var: SystemVar, val: T) -> Result<String> {
+        if !var.is_writable() {
+            return Err(Error::ReadOnly {
+                name: var.name().to_string(),
+            });
+        }
+        self.write_value(var.name(), val)
+    }
+
+    /// Reads a range of array elements in one pipelined batch and converts them to `T`.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - Name of the array, without index, such as `$IN` or `PARTS`.
+    /// * `dims` - First and last index for each of the one to three dimensions.
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted elements in row-major order, with the last index
+    /// changing fastest.
+    /// Returns `Error::InvalidInput` if an index range is empty, starts at 0 or
+    /// ends above `MAX_ARRAY_INDEX`, or if the ranges cover more than
+    /// `MAX_ARRAY_ELEMENTS` elements. Otherwise returns the first error
+    /// reported for any element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// // Reads PARTS[1,1], PARTS[1,2], PARTS[1,3], PARTS[2,1], ...
+    /// match osv.read_array::<i32>("PARTS", &[1..=20, 1..=3]) {
+    ///     Ok(parts) => println!("Parts: {:?}", parts),
+    ///     Err(e) => println!("Reading error: {}", e),
+    /// }
+    /// ```
+    pub fn read_array<T: FromKrl>(
+        &mut self,
+        base: &str,
+        dims: &[RangeInclusive<u32>],
+    ) -> Result<Vec<T>> {
+        let names = array::element_names(base, dims)?;
+        let mut pipeline = self.pipeline();
+        for name in &names {
+            pipeline.read(name);
+        }
+        pipeline
+            .execute()?
+            .into_iter()
+            .map(|val| T::from_krl(&KrlValue::parse(&val?)?))
+            .collect()
+    }
+
+    /// Writes a range of array elements in one pipelined batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - Name of the array, without index, such as `$OUT` or `PARTS`.
+    /// * `dims` - First and last index for each of the one to three dimensions.
+    /// * `values` - One value per element, in row-major order. Elements whose
+    ///   value converts to `None` are left unchanged.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if every element was written.
+    /// Returns `Error::InvalidInput` if the index ranges are invalid as
+    /// described in `read_array`, or the number of values does not match them.
+    /// Otherwise returns the first error reported for any element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// match osv.write_array("$OUT", &[1..=4], &[true, false, true, false]) {
+    ///     Ok(_) => println!("Outputs written"),
+    ///     Err(e) => println!("Writing error: {}", e),
+    /// }
+    /// ```
+    pub fn write_array<T: ToKrl>(
+        &mut self,
+        base: &str,
+        dims: &[RangeInclusive<u32>],
+        values: &[T],
+    ) -> Result<()> {
+        let names = array::element_names(base, dims)?;
+        if names.len() != values.len() {
+            return Err(Error::InvalidInput(
+                "Number of values does not match the index ranges",
             ));
         }
 
-        // Return the variable value as a string
-        let var_value = String::from_utf8_lossy(&response[7..(7 + val_len as usize)]).to_string();
-        Ok(var_value)
+        // Format every value before sending anything
+        let mut writes = Vec::new();
+        for (name, val) in names.iter().zip(values) {
+            if let Some(val) = val.to_krl_value()? {
+                writes.push((name, val.to_krl()?));
+            }
+        }
+
+        let mut pipeline = self.pipeline();
+        for (name, val) in &writes {
+            pipeline.write(name, val);
+        }
+        for result in pipeline.execute()? {
+            result?;
+        }
+        Ok(())
+    }
+
+    /// Returns a facade for the digital and analog I/O of the controller.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// match osv.io().set_digital_out(3, true) {
+    ///     Ok(_) => println!("Output set"),
+    ///     Err(e) => println!("Writing error: {}", e),
+    /// }
+    /// ```
+    pub fn io(&mut self) -> Io<'_> {
+        Io::new(self)
+    }
+
+    /// Returns a sampler reading `var_names` on a fixed period.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_names` - Names of the variables to read in every sample.
+    /// * `period` - Time between two samples, such as 20 ms for 50 Hz.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Sampler`, which yields one `Sample` per tick.
+    /// Returns `Error::InvalidInput` if there are no variables or the period
+    /// is zero, or `Error::InvalidName` if a variable name is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// use std::time::Duration;
+    /// let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// let sampler = osv.sampler(&["$POS_ACT"], Duration::from_millis(20)).unwrap();
+    /// assert_eq!(sampler.stats().samples, 0);
+    /// ```
+    pub fn sampler(&mut self, var_names: &[&str], period: Duration) -> Result<Sampler<'_>> {
+        Sampler::new(self, var_names, period)
+    }
+
+    /// Moves the client to a background I/O thread and returns a cloneable handle to it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// let shared = osv.into_shared();
+    /// let other = shared.clone();
+    /// assert!(!other.is_connected());
+    /// ```
+    pub fn into_shared(self) -> SharedOpenShowVar {
+        SharedOpenShowVar::new(self)
+    }
+
+    /// Moves the client to a background polling thread for change notifications.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::OpenShowVar;
+    /// let osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    /// let subscriptions = osv.into_subscriptions();
+    /// assert_eq!(subscriptions.polled_variables(), 0);
+    /// ```
+    pub fn into_subscriptions(self) -> Subscriptions {
+        Subscriptions::new(self)
     }
 
     /// Terminates the TCP connection.
@@ -325,3 +935,70 @@ impl OpenShowVar {
         }
     }
 }
+
+/// Returns the value of a response, or the error matching its failure status.
+pub(crate) fn response_value(response: Response, var_name: &str, val: &str) -> Result<String> {
+    match (response.success, response.mode) {
+        (true, _) => Ok(response.value),
+        (false, Mode::Read) => Err(Error::VariableNotFound {
+            name: var_name.to_string(),
+        }),
+        (false, Mode::Write) => Err(Error::WriteRejected {
+            name: var_name.to_string(),
+            value: val.to_string(),
+        }),
+    }
+}
+
+/// Builds the error returned when an operation needs a connection but none exists.
+fn not_connected() -> Error {
+    Error::NotConnected
+}
+
+/// Builds the error returned for a frame whose body exceeds `max_frame_size`.
+pub(crate) fn frame_too_large(msg_len: usize, max_frame_size: usize) -> Error {
+    Error::ProtocolViolation(format!(
+        "Frame of {} bytes exceeds maximum size of {} bytes",
+        msg_len, max_frame_size
+    ))
+}
+
+/// Reads one complete frame (header and body) from the connection.
+///
+/// The body length is taken from the header, and reading continues until
+/// exactly that many bytes have arrived. Frames whose body exceeds
+/// `max_frame_size` are drained from the stream and rejected.
+///
+/// `partial` is set while a frame has been started but not completely read,
+/// so a failure at that point can be told apart from one between frames.
+fn read_frame<R: Read>(
+    reader: &mut R,
+    max_frame_size: usize,
+    partial: &mut bool,
+) -> Result<Vec<u8>> {
+    let mut header = [0u8; HEADER_LEN];
+    let started = loop {
+        match reader.read(&mut header) {
+            Ok(0) => return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+            Ok(n) => break n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    };
+    *partial = true;
+    reader.read_exact(&mut header[started..])?;
+    let (_, msg_len) = codec::header(&header).expect("header buffer is complete");
+
+    // Skip the oversized body so the stream stays aligned on frame boundaries
+    if msg_len > max_frame_size {
+        std::io::copy(&mut reader.take(msg_len as u64), &mut std::io::sink())?;
+        *partial = false;
+        return Err(frame_too_large(msg_len, max_frame_size));
+    }
+
+    let mut frame = vec![0u8; HEADER_LEN + msg_len];
+    frame[..HEADER_LEN].copy_from_slice(&header);
+    reader.read_exact(&mut frame[HEADER_LEN..])?;
+    *partial = false;
+    Ok(frame)
+}
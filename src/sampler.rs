@@ -0,0 +1,345 @@
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::OpenShowVar;
+
+/// Upper bounds of the histogram buckets, in microseconds, following a 1-2-5 series.
+const BUCKET_BOUNDS_US: [u64; 19] = [
+    10, 20, 50, 100, 200, 500, 1_000, 2_000, 5_000, 10_000, 20_000, 50_000, 100_000, 200_000,
+    500_000, 1_000_000, 2_000_000, 5_000_000, 10_000_000,
+];
+
+/// The `Sampler` structure reads a set of variables on a fixed period.
+///
+/// Every sample reads all variables in one pipelined batch. Samples are
+/// scheduled at fixed multiples of the period from the first sample, so
+/// delays do not accumulate. If a sample takes longer than the period, the
+/// ticks that have already passed are skipped and the sample is reported as an
+/// overrun. The delay between the scheduled and the actual start of each
+/// sample and the request latency are recorded in `SamplerStats`.
+/// Enable `OpenShowVarBuilder::nodelay` when sampling several variables, so
+/// the pipelined requests are not held back by Nagle's algorithm.
+///
+/// The sampler is an endless iterator of samples.
+///
+/// # Example
+///
+/// ```no_run
+/// use rs_openshowvar::OpenShowVar;
+/// use std::time::Duration;
+/// let mut osv = OpenShowVar::builder("192.168.1.10".to_string(), 7000)
+///     .nodelay(true)
+///     .connect()
+///     .unwrap();
+///
+/// // Sample at 50 Hz for ten seconds
+/// let mut sampler = osv.sampler(&["$POS_ACT", "$VEL_ACT"], Duration::from_millis(20)).unwrap();
+/// for sample in sampler.by_ref().take(500) {
+///     let sample = sample.unwrap();
+///     println!("{:?}: {:?}", sample.timestamp, sample.values);
+/// }
+/// println!("{}", sampler.stats());
+/// ```
+pub struct Sampler<'a> {
+    /// Connection the variables are read through.
+    osv: &'a mut OpenShowVar,
+    /// Names of the sampled variables.
+    var_names: Vec<String>,
+    /// Time between two samples.
+    period: Duration,
+    /// Scheduled start of the next sample, set by the first sample.
+    next_scheduled: Option<Instant>,
+    /// Tick number of the next sample.
+    next_index: u64,
+    /// Statistics of the samples taken so far.
+    stats: SamplerStats,
+}
+
+/// A set of values read by a `Sampler`.
+#[derive(Debug)]
+pub struct Sample {
+    /// Tick number of the sample, counting skipped ticks.
+    pub index: u64,
+    /// Time the sample was scheduled to start.
+    pub scheduled: Instant,
+    /// Time the sample actually started.
+    pub started: Instant,
+    /// Wall clock time the sample started.
+    pub timestamp: SystemTime,
+    /// Time from sending the batch until the last response arrived.
+    pub latency: Duration,
+    /// Whether the sample took so long that following ticks were skipped.
+    pub overrun: bool,
+    /// One result per variable, in the order given to the sampler.
+    pub values: Vec<Result<String>>,
+}
+
+impl Sample {
+    /// Returns the delay between the scheduled and the actual start.
+    pub fn jitter(&self) -> Duration {
+        self.started.saturating_duration_since(self.scheduled)
+    }
+}
+
+/// Statistics collected by a `Sampler`.
+#[derive(Debug, Clone, Default)]
+pub struct SamplerStats {
+    /// Number of samples taken, including failed ones.
+    pub samples: u64,
+    /// Number of samples that took longer than the period.
+    pub overruns: u64,
+    /// Number of ticks skipped because of overruns.
+    pub skipped: u64,
+    /// Delay between the scheduled and the actual start of each sample.
+    pub jitter: Histogram,
+    /// Request latency of each successful sample.
+    pub latency: Histogram,
+}
+
+impl fmt::Display for SamplerStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} samples, {} overruns, {} skipped ticks",
+            self.samples, self.overruns, self.skipped
+        )?;
+        writeln!(f, "jitter:  {}", self.jitter)?;
+        write!(f, "latency: {}", self.latency)
+    }
+}
+
+/// A histogram of durations.
+///
+/// Durations are counted in buckets from 10 µs to 10 s following a 1-2-5
+/// series, plus an overflow bucket. The minimum, maximum and mean are exact;
+/// percentiles are reported as the upper bound of their bucket.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::Histogram;
+/// use std::time::Duration;
+/// let mut histogram = Histogram::new();
+/// histogram.record(Duration::from_micros(150));
+/// histogram.record(Duration::from_micros(700));
+/// assert_eq!(histogram.count(), 2);
+/// assert_eq!(histogram.max(), Some(Duration::from_micros(700)));
+/// assert_eq!(histogram.percentile(50.0), Some(Duration::from_micros(200)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Histogram {
+    /// Count per bucket; the last bucket counts durations above 10 s.
+    counts: [u64; BUCKET_BOUNDS_US.len() + 1],
+    /// Number of recorded durations.
+    count: u64,
+    /// Sum of the recorded durations.
+    sum: Duration,
+    /// Smallest recorded duration.
+    min: Option<Duration>,
+    /// Largest recorded duration.
+    max: Option<Duration>,
+}
+
+impl Histogram {
+    /// Creates an empty histogram.
+    pub fn new() -> Histogram {
+        Histogram::default()
+    }
+
+    /// Records a duration.
+    pub fn record(&mut self, duration: Duration) {
+        let micros = duration.as_micros();
+        let bucket = BUCKET_BOUNDS_US
+            .iter()
+            .position(|&bound| micros <= bound as u128)
+            .unwrap_or(BUCKET_BOUNDS_US.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum += duration;
+        self.min = Some(self.min.map_or(duration, |min| min.min(duration)));
+        self.max = Some(self.max.map_or(duration, |max| max.max(duration)));
+    }
+
+    /// Returns the number of recorded durations.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the smallest recorded duration.
+    pub fn min(&self) -> Option<Duration> {
+        self.min
+    }
+
+    /// Returns the largest recorded duration.
+    pub fn max(&self) -> Option<Duration> {
+        self.max
+    }
+
+    /// Returns the mean of the recorded durations.
+    pub fn mean(&self) -> Option<Duration> {
+        (self.count > 0).then(|| self.sum.div_f64(self.count as f64))
+    }
+
+    /// Returns the upper bound of the bucket holding the `p`-th percentile.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Percentile from 0 to 100.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if nothing was recorded. Percentiles falling into the
+    /// overflow bucket are reported as the largest recorded duration.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = ((p.clamp(0.0, 100.0) / 100.0 * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return match BUCKET_BOUNDS_US.get(bucket) {
+                    Some(&bound) => Some(Duration::from_micros(bound)),
+                    None => self.max,
+                };
+            }
+        }
+        self.max
+    }
+
+    /// Returns the upper bound and count of every non-empty bucket.
+    ///
+    /// The overflow bucket is reported with an upper bound of `Duration::MAX`.
+    pub fn buckets(&self) -> Vec<(Duration, u64)> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(bucket, &count)| {
+                let bound = BUCKET_BOUNDS_US
+                    .get(bucket)
+                    .map_or(Duration::MAX, |&bound| Duration::from_micros(bound));
+                (bound, count)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.min, self.mean(), self.percentile(99.0), self.max) {
+            (Some(min), Some(mean), Some(p99), Some(max)) => write!(
+                f,
+                "min {:?}, mean {:?}, p99 <= {:?}, max {:?}",
+                min, mean, p99, max
+            ),
+            _ => f.write_str("no samples"),
+        }
+    }
+}
+
+impl<'a> Sampler<'a> {
+    /// Creates a sampler reading `var_names` every `period` on the given connection.
+    pub(crate) fn new(
+        osv: &'a mut OpenShowVar,
+        var_names: &[&str],
+        period: Duration,
+    ) -> Result<Sampler<'a>> {
+        if var_names.is_empty() {
+            return Err(Error::InvalidInput("No variables to sample"));
+        }
+        if period.is_zero() {
+            return Err(Error::InvalidInput("Sample period must not be zero"));
+        }
+        for var_name in var_names {
+            codec::validate_name(var_name)?;
+        }
+        Ok(Sampler {
+            osv,
+            var_names: var_names.iter().map(|name| name.to_string()).collect(),
+            period,
+            next_scheduled: None,
+            next_index: 0,
+            stats: SamplerStats::default(),
+        })
+    }
+
+    /// Returns the time between two samples.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns the statistics of the samples taken so far.
+    pub fn stats(&self) -> &SamplerStats {
+        &self.stats
+    }
+
+    /// Waits for the next tick and reads every variable in one pipelined batch.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Sample` with its timing.
+    /// Returns `Error` if the connection fails during the batch; the schedule
+    /// continues with the next tick.
+    ///
+    /// If a `ReconnectPolicy` is configured, a batch that fails because the
+    /// connection was lost is sent again after reconnecting. The reconnect
+    /// time counts towards the latency of the sample.
+    pub fn next_sample(&mut self) -> Result<Sample> {
+        // The first sample starts right away and fixes the schedule
+        let scheduled = *self.next_scheduled.get_or_insert_with(Instant::now);
+        let index = self.next_index;
+        let now = Instant::now();
+        if scheduled > now {
+            thread::sleep(scheduled - now);
+        }
+
+        let started = Instant::now();
+        let timestamp = SystemTime::now();
+        let names: Vec<&str> = self.var_names.iter().map(String::as_str).collect();
+        let values = self.osv.read_many(&names);
+        let finished = Instant::now();
+        let latency = finished - started;
+
+        // Skip ticks that passed while the batch was running
+        let mut next = scheduled + self.period;
+        let mut next_index = index + 1;
+        let overrun = next < finished;
+        if overrun {
+            let behind = (finished - next).as_nanos() / self.period.as_nanos() + 1;
+            let behind = behind.min(u32::MAX as u128) as u32;
+            next += self.period * behind;
+            next_index += behind as u64;
+            self.stats.overruns += 1;
+            self.stats.skipped += behind as u64;
+        }
+        self.next_scheduled = Some(next);
+        self.next_index = next_index;
+
+        self.stats.samples += 1;
+        self.stats.jitter.record(started - scheduled);
+        let values = values?;
+        self.stats.latency.record(latency);
+        Ok(Sample {
+            index,
+            scheduled,
+            started,
+            timestamp,
+            latency,
+            overrun,
+            values,
+        })
+    }
+}
+
+impl Iterator for Sampler<'_> {
+    type Item = Result<Sample>;
+
+    fn next(&mut self) -> Option<Result<Sample>> {
+        Some(self.next_sample())
+    }
+}
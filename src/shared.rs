@@ -0,0 +1,161 @@
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use crate::error::{Error, Result};
+use crate::health::ConnectionState;
+use crate::snapshot::RobotState;
+use crate::OpenShowVar;
+
+/// A request queued for the I/O thread, run against the owned `OpenShowVar`.
+type Job = Box<dyn FnOnce(&mut OpenShowVar) + Send>;
+
+/// The `SharedOpenShowVar` structure is a cloneable handle to one `OpenShowVar` connection.
+///
+/// The connection is owned by a background I/O thread. Every handle queues its
+/// requests to that thread, which runs them one after another, so many threads
+/// can share a single proxy connection without locking it themselves. The I/O
+/// thread disconnects and exits once the last handle is dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// use rs_openshowvar::OpenShowVar;
+/// use std::thread;
+/// let mut osv = OpenShowVar::new("192.168.1.10".to_string(), 7000);
+/// osv.connect().unwrap();
+/// let shared = osv.into_shared();
+///
+/// let logger = shared.clone();
+/// let handle = thread::spawn(move || logger.read("$POS_ACT"));
+/// shared.write("$OV_PRO", "50").unwrap();
+/// handle.join().unwrap().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct SharedOpenShowVar {
+    /// Queue of requests processed by the I/O thread.
+    jobs: Sender<Job>,
+}
+
+impl SharedOpenShowVar {
+    /// Moves the client to a new I/O thread and returns the first handle to it.
+    ///
+    /// The client keeps its settings and connection state, so it may be
+    /// connected before or after being shared.
+    ///
+    /// # Arguments
+    ///
+    /// * `osv` - Client whose connection is shared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_openshowvar::{OpenShowVar, SharedOpenShowVar};
+    /// let shared = SharedOpenShowVar::new(OpenShowVar::new("127.0.0.1".to_string(), 7000));
+    /// assert!(!shared.is_connected());
+    /// ```
+    pub fn new(mut osv: OpenShowVar) -> SharedOpenShowVar {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        thread::Builder::new()
+            .name("openshowvar-io".to_string())
+            .spawn(move || {
+                // Run requests in arrival order until every handle is dropped
+                for job in queue {
+                    job(&mut osv);
+                }
+                osv.disconnect();
+            })
+            .expect("failed to spawn the OpenShowVar I/O thread");
+        SharedOpenShowVar { jobs }
+    }
+
+    /// Runs `request` on the I/O thread and waits for its result.
+    fn call<T: Send + 'static>(
+        &self,
+        request: impl FnOnce(&mut OpenShowVar) -> T + Send + 'static,
+    ) -> Result<T> {
+        let (reply, result) = mpsc::sync_channel(1);
+        let job: Job = Box::new(move |osv| {
+            let _ = reply.send(request(osv));
+        });
+        // The I/O thread only stops early if a request panicked
+        self.jobs.send(job).map_err(|_| io_thread_stopped())?;
+        result.recv().map_err(|_| io_thread_stopped())
+    }
+
+    /// Connects the shared client to the TCP server.
+    pub fn connect(&self) -> Result<()> {
+        self.call(|osv| osv.connect())?
+    }
+
+    /// Checks if the shared connection is open and the peer has not closed it.
+    pub fn is_connected(&self) -> bool {
+        self.call(|osv| osv.is_connected()).unwrap_or(false)
+    }
+
+    /// Probes the shared connection as described in `OpenShowVar::health`.
+    pub fn health(&self) -> ConnectionState {
+        self.call(|osv| osv.health())
+            .unwrap_or(ConnectionState::Disconnected)
+    }
+
+    /// Returns the number of stale responses discarded on the shared connection.
+    pub fn stale_responses(&self) -> u64 {
+        self.call(|osv| osv.stale_responses()).unwrap_or(0)
+    }
+
+    /// Reads the specified variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name` - Name of the variable to read.
+    ///
+    /// # Returns
+    ///
+    /// Returns the read variable value inside `Result<String>`.
+    pub fn read(&self, var_name: &str) -> Result<String> {
+        let var_name = var_name.to_string();
+        self.call(move |osv| osv.read(&var_name))?
+    }
+
+    /// Writes a value to the specified variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name` - Name of the variable to write.
+    /// * `val` - Value to write to the variable.
+    ///
+    /// # Returns
+    ///
+    /// Returns the written variable value inside `Result<String>`.
+    pub fn write(&self, var_name: &str, val: &str) -> Result<String> {
+        let var_name = var_name.to_string();
+        let val = val.to_string();
+        self.call(move |osv| osv.write(&var_name, &val))?
+    }
+
+    /// Reads several variables in one pipelined batch, as `OpenShowVar::read_many` does.
+    ///
+    /// Requests from other handles are not interleaved with the batch.
+    pub fn read_many(&self, var_names: &[&str]) -> Result<Vec<Result<String>>> {
+        let var_names: Vec<String> = var_names.iter().map(|name| name.to_string()).collect();
+        self.call(move |osv| {
+            let var_names: Vec<&str> = var_names.iter().map(String::as_str).collect();
+            osv.read_many(&var_names)
+        })?
+    }
+
+    /// Reads the state of the robot in one pipelined batch, as `OpenShowVar::snapshot` does.
+    pub fn snapshot(&self) -> Result<RobotState> {
+        self.call(|osv| osv.snapshot())?
+    }
+
+    /// Terminates the shared TCP connection for all handles.
+    pub fn disconnect(&self) {
+        let _ = self.call(|osv| osv.disconnect());
+    }
+}
+
+/// Error returned when the I/O thread is no longer running.
+fn io_thread_stopped() -> Error {
+    Error::Io(std::io::Error::other("OpenShowVar I/O thread stopped"))
+}
@@ -0,0 +1,164 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::error::Result;
+use crate::krl::convert::unexpected;
+use crate::krl::{E6Axis, E6Pos, FromKrl, KrlValue};
+use crate::system_var::SystemVar;
+use crate::OpenShowVar;
+
+/// Variables read by `OpenShowVar::snapshot`, in the order they are requested.
+const SNAPSHOT_VARS: [SystemVar; 14] = [
+    SystemVar::ModeOp,
+    SystemVar::OvPro,
+    SystemVar::PosAct,
+    SystemVar::AxisAct,
+    SystemVar::VelAct,
+    SystemVar::ProState1,
+    SystemVar::ProName,
+    SystemVar::StopMess,
+    SystemVar::DrivesOn,
+    SystemVar::PeriRdy,
+    SystemVar::UserSaf,
+    SystemVar::AlarmStop,
+    SystemVar::ActTool,
+    SystemVar::ActBase,
+];
+
+/// Operating mode of the controller, read from `$MODE_OP`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatingMode {
+    /// Manual reduced velocity (`#T1`).
+    T1,
+    /// Manual high velocity (`#T2`).
+    T2,
+    /// Automatic (`#AUT`).
+    Aut,
+    /// Automatic external (`#EX`).
+    Ex,
+}
+
+impl FromKrl for OperatingMode {
+    fn from_krl(value: &KrlValue) -> Result<Self> {
+        match value.as_enum().map(str::to_ascii_uppercase).as_deref() {
+            Some("T1") => Ok(OperatingMode::T1),
+            Some("T2") => Ok(OperatingMode::T2),
+            Some("AUT") => Ok(OperatingMode::Aut),
+            Some("EX") => Ok(OperatingMode::Ex),
+            _ => Err(unexpected("MODE_OP", value)),
+        }
+    }
+}
+
+/// State of a KRL interpreter, read from `$PRO_STATE1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgramState {
+    /// No program is selected (`#P_FREE`).
+    Free,
+    /// A program is selected and reset (`#P_RESET`).
+    Reset,
+    /// The program is running (`#P_ACTIVE`).
+    Active,
+    /// The program is stopped (`#P_STOP`).
+    Stop,
+    /// The program has ended (`#P_END`).
+    End,
+}
+
+impl FromKrl for ProgramState {
+    fn from_krl(value: &KrlValue) -> Result<Self> {
+        match value.as_enum().map(str::to_ascii_uppercase).as_deref() {
+            Some("P_FREE") => Ok(ProgramState::Free),
+            Some("P_RESET") => Ok(ProgramState::Reset),
+            Some("P_ACTIVE") => Ok(ProgramState::Active),
+            Some("P_STOP") => Ok(ProgramState::Stop),
+            Some("P_END") => Ok(ProgramState::End),
+            _ => Err(unexpected("PRO_STATE", value)),
+        }
+    }
+}
+
+/// State of the robot, read by `OpenShowVar::snapshot` in one pipelined batch.
+///
+/// All values are requested back-to-back, so they were sampled between
+/// `timestamp` and `timestamp + duration`.
+///
+/// The mode, override, position and program state are required. The other
+/// values are `None` if the controller cannot read them or returns a value
+/// of another type, as older KSS versions or missing options may do.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobotState {
+    /// Time the batch was sent.
+    pub timestamp: SystemTime,
+    /// Time from sending the batch until the last response arrived.
+    pub duration: Duration,
+    /// Operating mode (`$MODE_OP`).
+    pub mode_op: OperatingMode,
+    /// Program override in percent (`$OV_PRO`).
+    pub ov_pro: i32,
+    /// Current Cartesian position (`$POS_ACT`).
+    pub pos_act: E6Pos,
+    /// Current axis positions (`$AXIS_ACT`).
+    pub axis_act: E6Axis,
+    /// Current path velocity in m/s (`$VEL_ACT`).
+    pub vel_act: Option<f64>,
+    /// State of the robot interpreter (`$PRO_STATE1`).
+    pub pro_state: ProgramState,
+    /// Name of the selected robot program (`$PRO_NAME[]`).
+    pub pro_name: Option<String>,
+    /// A stop message is active (`$STOPMESS`).
+    pub stop_mess: Option<bool>,
+    /// The drives are switched on (`$DRIVES_ON`).
+    pub drives_on: Option<bool>,
+    /// The drives are ready (`$PERI_RDY`).
+    pub peri_rdy: Option<bool>,
+    /// The safety gate or enabling switch is closed (`$USER_SAF`).
+    pub user_saf: Option<bool>,
+    /// No emergency stop is active (`$ALARM_STOP`).
+    pub alarm_stop: Option<bool>,
+    /// Number of the active tool (`$ACT_TOOL`).
+    pub act_tool: Option<i32>,
+    /// Number of the active base (`$ACT_BASE`).
+    pub act_base: Option<i32>,
+}
+
+impl RobotState {
+    /// Reads every snapshot variable in one pipelined batch.
+    pub(crate) fn read(osv: &mut OpenShowVar) -> Result<RobotState> {
+        let timestamp = SystemTime::now();
+        let start = Instant::now();
+        let names = SNAPSHOT_VARS.map(SystemVar::name);
+        let values = osv.read_many(&names)?;
+        let duration = start.elapsed();
+
+        // Parse the values in the order they were requested.
+        // Required values fail the snapshot, optional ones become `None`.
+        let mut values = values.into_iter();
+        let mut next = || -> Result<KrlValue> {
+            let val = values.next().expect("one value per snapshot variable")?;
+            Ok(KrlValue::parse(&val)?)
+        };
+        Ok(RobotState {
+            timestamp,
+            duration,
+            mode_op: convert(next())?,
+            ov_pro: convert(next())?,
+            pos_act: convert(next())?,
+            axis_act: convert(next())?,
+            vel_act: convert(next()).ok(),
+            pro_state: convert(next())?,
+            pro_name: convert(next()).ok(),
+            stop_mess: convert(next()).ok(),
+            drives_on: convert(next()).ok(),
+            peri_rdy: convert(next()).ok(),
+            user_saf: convert(next()).ok(),
+            alarm_stop: convert(next()).ok(),
+            act_tool: convert(next()).ok(),
+            act_base: convert(next()).ok(),
+        })
+    }
+}
+
+/// Converts a parsed snapshot value, passing on the error of a failed read.
+fn convert<T: FromKrl>(value: Result<KrlValue>) -> Result<T> {
+    T::from_krl(&value?)
+}
@@ -0,0 +1,479 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::krl::KrlValue;
+use crate::OpenShowVar;
+
+/// Callback invoked on the polling thread for every change event.
+type Callback = Box<dyn FnMut(&ChangeEvent) + Send>;
+
+/// A change of a subscribed variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    /// Name of the variable as given to `Watch::new`.
+    pub name: String,
+    /// Value last delivered to the subscriber, or `None` for the first event.
+    pub old: Option<KrlValue>,
+    /// Value that was read.
+    pub new: KrlValue,
+    /// Time the poll that read the value was sent.
+    pub timestamp: SystemTime,
+}
+
+/// The `Watch` structure describes a variable to subscribe to.
+///
+/// # Example
+///
+/// ```
+/// use rs_openshowvar::Watch;
+/// use std::time::Duration;
+/// let watch = Watch::new("$VEL_ACT", Duration::from_millis(100)).deadband(0.01);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Watch {
+    /// Name of the variable to poll.
+    name: String,
+    /// Time between two polls of the variable.
+    interval: Duration,
+    /// Largest change of a REAL that is not reported.
+    deadband: f64,
+}
+
+impl Watch {
+    /// Creates a watch polling `name` every `interval`, reporting every change.
+    pub fn new(name: &str, interval: Duration) -> Watch {
+        Watch {
+            name: name.to_string(),
+            interval,
+            deadband: 0.0,
+        }
+    }
+
+    /// Sets the largest change of a REAL that is not reported.
+    ///
+    /// The deadband applies to REAL values and to the REAL members of
+    /// structures, such as the coordinates of `$POS_ACT`. Changes are measured
+    /// against the value last delivered, so slow drifts are still reported
+    /// once they exceed the deadband.
+    pub fn deadband(mut self, deadband: f64) -> Self {
+        self.deadband = deadband;
+        self
+    }
+}
+
+/// The `Subscriptions` structure polls subscribed variables and reports their changes.
+///
+/// KukaVarProxy has no push mechanism, so a background polling thread owns
+/// the connection and reads the subscribed variables at their poll
+/// intervals. Variables that are due at the same time are read in one
+/// pipelined batch, and a variable watched by several subscribers is read
+/// only once. Changes are delivered through a `Subscription` channel or a
+/// callback. Failed reads are skipped and counted.
+///
+/// The polling thread disconnects and exits when `Subscriptions` is dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// use rs_openshowvar::{OpenShowVar, Watch};
+/// use std::time::Duration;
+/// let mut osv = OpenShowVar::new("192.168.1.10".to_string(), 7000);
+/// osv.connect().unwrap();
+/// let subscriptions = osv.into_subscriptions();
+///
+/// let velocity = subscriptions
+///     .subscribe(Watch::new("$VEL_ACT", Duration::from_millis(50)).deadband(0.001))
+///     .unwrap();
+/// for event in velocity.iter() {
+///     println!("{}: {:?} -> {}", event.name, event.old, event.new);
+/// }
+/// ```
+pub struct Subscriptions {
+    /// State shared with the polling thread and the subscriptions.
+    shared: Arc<Shared>,
+    /// Polling thread, joined when dropped.
+    poller: Option<JoinHandle<()>>,
+}
+
+/// State shared between the polling thread and its handles.
+struct Shared {
+    /// Subscribed variables and polling statistics.
+    state: Mutex<State>,
+    /// Signaled when subscriptions change or polling stops.
+    wake: Condvar,
+}
+
+/// Variables being polled.
+struct State {
+    /// One entry per distinct variable.
+    variables: Vec<Variable>,
+    /// Identifier of the next subscriber.
+    next_id: u64,
+    /// Number of failed variable reads.
+    failed_reads: u64,
+    /// Set when the polling thread has to exit.
+    stop: bool,
+}
+
+/// A polled variable and everyone subscribed to it.
+struct Variable {
+    /// Name of the variable as sent to the controller.
+    name: String,
+    /// Subscribers of the variable.
+    subscribers: Vec<Subscriber>,
+}
+
+/// A single subscription to a variable.
+struct Subscriber {
+    /// Identifier used to unsubscribe.
+    id: u64,
+    /// Time between two polls.
+    interval: Duration,
+    /// Largest change of a REAL that is not reported.
+    deadband: f64,
+    /// Time the subscriber is due to be polled again.
+    next_due: Instant,
+    /// Value last delivered to the subscriber.
+    last: Option<KrlValue>,
+    /// Where change events are delivered.
+    sink: Sink,
+}
+
+/// Destination of change events.
+#[derive(Clone)]
+enum Sink {
+    /// Events are sent over a channel.
+    Channel(Sender<ChangeEvent>),
+    /// Events are passed to a callback.
+    Callback(Arc<Mutex<Callback>>),
+}
+
+impl Subscriptions {
+    /// Moves the client to a new polling thread.
+    ///
+    /// The client should be connected, since the polling thread does not
+    /// connect it. Configure a `ReconnectPolicy` so polling resumes after the
+    /// connection is lost, for example when the proxy restarts; without one,
+    /// every later poll fails and is only counted in `failed_reads`.
+    ///
+    /// # Arguments
+    ///
+    /// * `osv` - Client used to poll the variables.
+    pub fn new(mut osv: OpenShowVar) -> Subscriptions {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                variables: Vec::new(),
+                next_id: 0,
+                failed_reads: 0,
+                stop: false,
+            }),
+            wake: Condvar::new(),
+        });
+        let poller = {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name("openshowvar-poll".to_string())
+                .spawn(move || {
+                    while shared.poll(&mut osv) {}
+                    osv.disconnect();
+                })
+                .expect("failed to spawn the OpenShowVar polling thread")
+        };
+        Subscriptions {
+            shared,
+            poller: Some(poller),
+        }
+    }
+
+    /// Subscribes to a variable and receives its changes over a channel.
+    ///
+    /// The first event carries the current value with `old` set to `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `watch` - Variable, poll interval and deadband.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Subscription`, which unsubscribes when dropped.
+    /// Returns `Error::InvalidName` if the variable name is invalid, or
+    /// `Error::InvalidInput` if the interval is zero or the deadband is negative.
+    pub fn subscribe(&self, watch: Watch) -> Result<Subscription> {
+        let (sender, events) = mpsc::channel();
+        let id = self.add(watch, Sink::Channel(sender))?;
+        Ok(Subscription {
+            shared: Arc::clone(&self.shared),
+            id,
+            events,
+        })
+    }
+
+    /// Subscribes to a variable and passes its changes to `callback`.
+    ///
+    /// The callback runs on the polling thread, so it should return quickly.
+    /// The events channel of the returned `Subscription` stays empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `watch` - Variable, poll interval and deadband.
+    /// * `callback` - Function called with every change event.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Subscription`, which unsubscribes when dropped.
+    /// Returns `Error` as described in `subscribe`.
+    pub fn subscribe_with(
+        &self,
+        watch: Watch,
+        callback: impl FnMut(&ChangeEvent) + Send + 'static,
+    ) -> Result<Subscription> {
+        let callback: Callback = Box::new(callback);
+        let id = self.add(watch, Sink::Callback(Arc::new(Mutex::new(callback))))?;
+        // The sender is dropped right away, since events go to the callback
+        let (_, events) = mpsc::channel();
+        Ok(Subscription {
+            shared: Arc::clone(&self.shared),
+            id,
+            events,
+        })
+    }
+
+    /// Returns the number of distinct variables being polled.
+    pub fn polled_variables(&self) -> usize {
+        self.shared.lock().variables.len()
+    }
+
+    /// Returns the number of variable reads that failed and were skipped.
+    pub fn failed_reads(&self) -> u64 {
+        self.shared.lock().failed_reads
+    }
+
+    /// Registers a subscriber, sharing the variable with existing subscribers.
+    fn add(&self, watch: Watch, sink: Sink) -> Result<u64> {
+        codec::validate_name(&watch.name)?;
+        if watch.interval.is_zero() {
+            return Err(Error::InvalidInput("Poll interval must not be zero"));
+        }
+        if watch.deadband.is_nan() || watch.deadband < 0.0 {
+            return Err(Error::InvalidInput("Deadband must not be negative"));
+        }
+
+        let mut state = self.shared.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+        let subscriber = Subscriber {
+            id,
+            interval: watch.interval,
+            deadband: watch.deadband,
+            next_due: Instant::now(),
+            last: None,
+            sink,
+        };
+        // KRL names are case-insensitive, so `$ov_pro` and `$OV_PRO` are read once
+        match state
+            .variables
+            .iter_mut()
+            .find(|var| var.name.eq_ignore_ascii_case(&watch.name))
+        {
+            Some(var) => var.subscribers.push(subscriber),
+            None => state.variables.push(Variable {
+                name: watch.name,
+                subscribers: vec![subscriber],
+            }),
+        }
+        drop(state);
+        self.shared.wake.notify_one();
+        Ok(id)
+    }
+}
+
+impl Drop for Subscriptions {
+    fn drop(&mut self) {
+        self.shared.lock().stop = true;
+        self.shared.wake.notify_one();
+        if let Some(poller) = self.poller.take() {
+            let _ = poller.join();
+        }
+        // Drop the senders, so waiting subscriptions see that polling stopped
+        self.shared.lock().variables.clear();
+    }
+}
+
+impl Shared {
+    /// Waits until variables are due, reads them and delivers their changes.
+    ///
+    /// Returns `false` once polling has to stop.
+    fn poll(&self, osv: &mut OpenShowVar) -> bool {
+        let state = self.lock();
+        if state.stop {
+            return false;
+        }
+        let now = Instant::now();
+        let due: Vec<String> = state
+            .variables
+            .iter()
+            .filter(|var| var.subscribers.iter().any(|sub| sub.next_due <= now))
+            .map(|var| var.name.clone())
+            .collect();
+        if due.is_empty() {
+            // Sleep until the next subscriber is due or the subscriptions change
+            let next_due = state
+                .variables
+                .iter()
+                .flat_map(|var| var.subscribers.iter().map(|sub| sub.next_due))
+                .min();
+            match next_due {
+                Some(next_due) => {
+                    let timeout = next_due.saturating_duration_since(now);
+                    drop(self.wake.wait_timeout(state, timeout));
+                }
+                None => drop(self.wake.wait(state)),
+            }
+            return true;
+        }
+        drop(state);
+
+        // Read every due variable in one batch, without holding the lock
+        let timestamp = SystemTime::now();
+        let names: Vec<&str> = due.iter().map(String::as_str).collect();
+        let values: Vec<Option<KrlValue>> = match osv.read_many(&names) {
+            Ok(values) => values
+                .into_iter()
+                .map(|val| val.ok().and_then(|val| KrlValue::parse(&val).ok()))
+                .collect(),
+            Err(_) => vec![None; due.len()],
+        };
+
+        let mut deliveries = Vec::new();
+        let mut state = self.lock();
+        state.failed_reads += values.iter().filter(|val| val.is_none()).count() as u64;
+        for (name, value) in due.iter().zip(values) {
+            // The variable may have been unsubscribed during the read
+            let Some(var) = state.variables.iter_mut().find(|var| &var.name == name) else {
+                continue;
+            };
+            for sub in var.subscribers.iter_mut().filter(|sub| sub.next_due <= now) {
+                sub.next_due += sub.interval;
+                if sub.next_due <= now {
+                    // Skip missed polls instead of catching up with a burst
+                    sub.next_due = now + sub.interval;
+                }
+                let Some(new) = &value else {
+                    continue;
+                };
+                if sub
+                    .last
+                    .as_ref()
+                    .is_some_and(|old| !changed(old, new, sub.deadband))
+                {
+                    continue;
+                }
+                let event = ChangeEvent {
+                    name: name.clone(),
+                    old: sub.last.replace(new.clone()),
+                    new: new.clone(),
+                    timestamp,
+                };
+                deliveries.push((sub.sink.clone(), event));
+            }
+        }
+        drop(state);
+
+        // Deliver outside the lock, so callbacks may subscribe or unsubscribe
+        for (sink, event) in deliveries {
+            match sink {
+                Sink::Channel(sender) => {
+                    let _ = sender.send(event);
+                }
+                Sink::Callback(callback) => {
+                    (callback.lock().unwrap_or_else(|e| e.into_inner()))(&event);
+                }
+            }
+        }
+        true
+    }
+
+    /// Locks the state, recovering it if another thread panicked.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Checks whether `new` differs from `old` by more than the REAL deadband.
+fn changed(old: &KrlValue, new: &KrlValue, deadband: f64) -> bool {
+    match (old, new) {
+        (KrlValue::Real(old), KrlValue::Real(new)) => (new - old).abs() > deadband,
+        (
+            KrlValue::Struct {
+                type_name: old_type,
+                fields: old_fields,
+            },
+            KrlValue::Struct {
+                type_name: new_type,
+                fields: new_fields,
+            },
+        ) => {
+            old_type != new_type
+                || old_fields.len() != new_fields.len()
+                || old_fields
+                    .iter()
+                    .zip(new_fields)
+                    .any(|((old_name, old), (new_name, new))| {
+                        old_name != new_name || changed(old, new, deadband)
+                    })
+        }
+        _ => old != new,
+    }
+}
+
+/// A subscription to a variable, created by `Subscriptions::subscribe`.
+///
+/// Dropping it unsubscribes. The variable stops being polled once its last
+/// subscription is dropped.
+pub struct Subscription {
+    /// State of the polling thread.
+    shared: Arc<Shared>,
+    /// Identifier of the subscriber.
+    id: u64,
+    /// Change events of the variable.
+    events: Receiver<ChangeEvent>,
+}
+
+impl Subscription {
+    /// Waits for the next change event.
+    ///
+    /// Returns `None` once polling has stopped or the subscription delivers
+    /// its events to a callback.
+    pub fn recv(&self) -> Option<ChangeEvent> {
+        self.events.recv().ok()
+    }
+
+    /// Waits up to `timeout` for the next change event.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ChangeEvent> {
+        self.events.recv_timeout(timeout).ok()
+    }
+
+    /// Returns the next change event if one is waiting.
+    pub fn try_recv(&self) -> Option<ChangeEvent> {
+        self.events.try_recv().ok()
+    }
+
+    /// Returns an iterator waiting for change events until polling stops.
+    pub fn iter(&self) -> impl Iterator<Item = ChangeEvent> + '_ {
+        self.events.iter()
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        for var in &mut state.variables {
+            var.subscribers.retain(|sub| sub.id != self.id);
+        }
+        state.variables.retain(|var| !var.subscribers.is_empty());
+    }
+}
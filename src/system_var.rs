@@ -0,0 +1,157 @@
+use std::fmt;
+
+/// Whether a system variable may be written through KukaVarProxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// The variable can only be read.
+    ReadOnly,
+    /// The variable can be read and written.
+    ReadWrite,
+}
+
+/// Metadata of a KUKA system variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemVarInfo {
+    /// Variable name as sent to the controller, such as `$OV_PRO`.
+    pub name: &'static str,
+    /// KRL data type, such as `INT`, `E6POS` or `CHAR[]`.
+    pub krl_type: &'static str,
+    /// Whether the variable can be written.
+    pub access: Access,
+    /// Unit of the value, if it has one.
+    pub unit: Option<&'static str>,
+    /// Short description of the value.
+    pub description: &'static str,
+}
+
+// Declares the `SystemVar` enum together with the metadata of each variable.
+macro_rules! system_vars {
+    ($(
+        $(#[$meta:meta])*
+        $var:ident => $name:literal, $krl_type:literal, $access:ident, $unit:expr, $description:literal;
+    )*) => {
+        /// A KUKA system variable from the built-in catalog.
+        ///
+        /// Each variable carries its KRL type, access mode, unit and a short
+        /// description. `OpenShowVar::read_system` and `write_system` use the
+        /// catalog to read typed values and to refuse writes to read-only
+        /// variables before anything is sent.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use rs_openshowvar::{Access, SystemVar};
+        /// let info = SystemVar::PosAct.info();
+        /// assert_eq!(info.name, "$POS_ACT");
+        /// assert_eq!(info.krl_type, "E6POS");
+        /// assert_eq!(info.access, Access::ReadOnly);
+        /// assert_eq!(SystemVar::from_name("$ov_pro"), Some(SystemVar::OvPro));
+        /// ```
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum SystemVar {
+            $(
+                $(#[$meta])*
+                $var,
+            )*
+        }
+
+        impl SystemVar {
+            /// Every variable in the catalog.
+            pub const ALL: &'static [SystemVar] = &[$(SystemVar::$var,)*];
+
+            /// Returns the metadata of the variable.
+            pub fn info(self) -> &'static SystemVarInfo {
+                match self {
+                    $(
+                        SystemVar::$var => &SystemVarInfo {
+                            name: $name,
+                            krl_type: $krl_type,
+                            access: Access::$access,
+                            unit: $unit,
+                            description: $description,
+                        },
+                    )*
+                }
+            }
+        }
+    };
+}
+
+system_vars! {
+    /// Program override.
+    OvPro => "$OV_PRO", "INT", ReadWrite, Some("%"), "Program override";
+    /// Jog override.
+    OvJog => "$OV_JOG", "INT", ReadWrite, Some("%"), "Jog override";
+    /// Operating mode.
+    ModeOp => "$MODE_OP", "ENUM MODE_OP", ReadOnly, None, "Operating mode (#T1, #T2, #AUT, #EX)";
+    /// Current Cartesian position.
+    PosAct => "$POS_ACT", "E6POS", ReadOnly, Some("mm, deg"), "Current Cartesian position of the TCP in the base coordinate system";
+    /// Current axis positions.
+    AxisAct => "$AXIS_ACT", "E6AXIS", ReadOnly, Some("deg"), "Current axis positions";
+    /// Current path velocity.
+    VelAct => "$VEL_ACT", "REAL", ReadOnly, Some("m/s"), "Current path velocity of the TCP";
+    /// Current tool frame.
+    Tool => "$TOOL", "FRAME", ReadWrite, Some("mm, deg"), "Current tool frame relative to the flange";
+    /// Current base frame.
+    Base => "$BASE", "FRAME", ReadWrite, Some("mm, deg"), "Current base frame relative to the world";
+    /// Current payload.
+    Load => "$LOAD", "LOAD", ReadWrite, Some("kg, mm, kg m2"), "Current payload data";
+    /// Number of the active tool.
+    ActTool => "$ACT_TOOL", "INT", ReadOnly, None, "Number of the active tool";
+    /// Number of the active base.
+    ActBase => "$ACT_BASE", "INT", ReadOnly, None, "Number of the active base";
+    /// Advance run.
+    Advance => "$ADVANCE", "INT", ReadWrite, None, "Maximum number of motion instructions in the advance run";
+    /// State of the robot interpreter.
+    ProState1 => "$PRO_STATE1", "ENUM PRO_STATE", ReadOnly, None, "State of the robot interpreter (#P_FREE, #P_RESET, #P_ACTIVE, #P_STOP, #P_END)";
+    /// State of the submit interpreter.
+    ProState0 => "$PRO_STATE0", "ENUM PRO_STATE", ReadOnly, None, "State of the submit interpreter";
+    /// Name of the selected robot program.
+    ProName => "$PRO_NAME[]", "CHAR[]", ReadOnly, None, "Name of the selected robot program";
+    /// Stop message active.
+    StopMess => "$STOPMESS", "BOOL", ReadOnly, None, "A stop message is active";
+    /// Drives switched on.
+    DrivesOn => "$DRIVES_ON", "BOOL", ReadOnly, None, "The drives are switched on";
+    /// Drives ready.
+    PeriRdy => "$PERI_RDY", "BOOL", ReadOnly, None, "The drives are ready";
+    /// User safety.
+    UserSaf => "$USER_SAF", "BOOL", ReadOnly, None, "The safety gate or enabling switch is closed";
+    /// Emergency or safety stop.
+    AlarmStop => "$ALARM_STOP", "BOOL", ReadOnly, None, "No emergency stop is active";
+    /// Robot on the programmed path.
+    OnPath => "$ON_PATH", "BOOL", ReadOnly, None, "The robot is on the programmed path";
+    /// Robot in home position.
+    InHome => "$IN_HOME", "BOOL", ReadOnly, None, "The robot is in its home position";
+    /// Robot name.
+    RobName => "$ROBNAME[]", "CHAR[]", ReadOnly, None, "Name of the robot";
+    /// Robot serial number.
+    KrSerialNo => "$KR_SERIALNO", "INT", ReadOnly, None, "Serial number of the robot";
+    /// Operating hours.
+    RobRuntime => "$ROBRUNTIME", "INT", ReadOnly, Some("min"), "Operating time of the robot";
+}
+
+impl SystemVar {
+    /// Returns the variable name as sent to the controller.
+    pub fn name(self) -> &'static str {
+        self.info().name
+    }
+
+    /// Returns `true` if the variable can be written.
+    pub fn is_writable(self) -> bool {
+        self.info().access == Access::ReadWrite
+    }
+
+    /// Looks up a variable by name, ignoring case as KRL does.
+    pub fn from_name(name: &str) -> Option<SystemVar> {
+        SystemVar::ALL
+            .iter()
+            .copied()
+            .find(|var| var.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for SystemVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
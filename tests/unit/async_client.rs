@@ -0,0 +1,145 @@
+use super::{
+    read_response, start_chunked_server, start_mock_server, start_resetting_server,
+    start_stalling_server,
+};
+use rs_openshowvar::{AsyncOpenShowVar, Error, OpenShowVar};
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+// Tests reading and writing through the async client against the mock server.
+#[tokio::test]
+async fn test_async_read_write() {
+    // Start the mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `AsyncOpenShowVar` instance and connect to the mock server
+    let mut osv = AsyncOpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().await.unwrap();
+    assert!(osv.is_connected());
+
+    assert_eq!(osv.read("$OV_PRO").await.unwrap(), "$OV_PRO");
+    assert_eq!(osv.write("$OV_PRO", "50").await.unwrap(), "50");
+
+    osv.disconnect().await;
+    assert!(!osv.is_connected());
+}
+
+// Tests that the async client reports the same errors as the blocking client.
+#[tokio::test]
+async fn test_async_errors_match_sync() {
+    // Start the mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    let mut osv = AsyncOpenShowVar::new(addr.ip().to_string(), addr.port());
+    assert!(matches!(
+        osv.read("$OV_PRO").await,
+        Err(Error::NotConnected)
+    ));
+    osv.connect().await.unwrap();
+
+    // Input validation happens before anything is sent
+    assert!(matches!(osv.read("").await, Err(Error::InvalidInput(_))));
+    assert!(matches!(
+        osv.write("$OV_PRO", "").await,
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        osv.read("$THIS_IDENTIFIER_IS_FAR_TOO_LONG").await,
+        Err(Error::InvalidName { .. })
+    ));
+
+    // Failure status trailers are mapped the same way
+    let async_err = osv.read("non_existing_var").await.unwrap_err();
+    let mut sync = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    sync.connect().unwrap();
+    let sync_err = sync.read("non_existing_var").unwrap_err();
+    assert_eq!(async_err.to_string(), sync_err.to_string());
+}
+
+// Tests that the async client discards stale replies and enforces the frame limit.
+#[tokio::test]
+async fn test_async_framing() {
+    // Start a mock server that sends a stale reply before the real one
+    let mut response = read_response(41, b"stale");
+    response.extend(read_response(0, b"fresh"));
+    let listener = start_chunked_server(response, 3);
+    let addr = listener.local_addr().unwrap();
+
+    let mut osv = AsyncOpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().await.unwrap();
+    assert_eq!(osv.read("$OV_PRO").await.unwrap(), "fresh");
+    assert_eq!(osv.stale_responses(), 1);
+
+    // Start a mock server that returns a 2000-byte value
+    let listener = start_chunked_server(read_response(0, &[b'A'; 2000]), 512);
+    let addr = listener.local_addr().unwrap();
+
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .max_frame_size(1024)
+        .build_async();
+    osv.connect().await.unwrap();
+    assert!(matches!(
+        osv.read("$PRO_NAME").await,
+        Err(Error::ProtocolViolation(_))
+    ));
+}
+
+// Tests that a server that never answers produces a timeout error.
+#[tokio::test]
+async fn test_async_read_timeout() {
+    // Start a mock server that accepts the connection but never replies
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let _handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(5));
+        drop(stream);
+    });
+
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .read_timeout(Duration::from_millis(200))
+        .build_async();
+    osv.connect().await.unwrap();
+    assert!(matches!(osv.read("$OV_PRO").await, Err(Error::Timeout)));
+}
+
+// Tests that a read cancelled by the timeout in the middle of a frame drops the connection.
+#[tokio::test]
+async fn test_async_partial_frame_timeout_drops_connection() {
+    // Start a mock server that stalls longer than the read timeout mid-reply
+    let listener = start_stalling_server(Duration::from_millis(300));
+    let addr = listener.local_addr().unwrap();
+
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .read_timeout(Duration::from_millis(100))
+        .build_async();
+    osv.connect().await.unwrap();
+
+    assert!(matches!(osv.read("A").await, Err(Error::Timeout)));
+    assert!(!osv.is_connected());
+
+    // The rest of the cut-off frame is never taken for a new header
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(matches!(osv.read("B").await, Err(Error::NotConnected)));
+}
+
+// Tests that a failed write drops the connection instead of leaving a half-sent frame behind.
+#[tokio::test]
+async fn test_async_failed_write_drops_connection() {
+    // Start a mock server that resets the connection before any request arrives
+    let listener = start_resetting_server();
+    let addr = listener.local_addr().unwrap();
+
+    let mut osv = AsyncOpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    assert!(matches!(osv.write("A", "1").await, Err(Error::Io(_))));
+    assert!(!osv.is_connected());
+
+    // The next request is not written to the broken socket
+    assert!(matches!(osv.read("A").await, Err(Error::NotConnected)));
+}
@@ -0,0 +1,158 @@
+use rs_openshowvar::codec::{self, Mode, Request, Response};
+use rs_openshowvar::Error;
+
+// Tests that a read request is encoded without a value field.
+#[test]
+fn test_encode_read_request() {
+    let frame = codec::encode(&Request::read(0x0102, "$OV_PRO")).unwrap();
+
+    // Header: message ID, body length (mode + name length + name)
+    assert_eq!(&frame[..4], &[0x01, 0x02, 0, 10]);
+    // Body: read mode, name length, name
+    assert_eq!(&frame[4..7], &[0, 0, 7]);
+    assert_eq!(&frame[7..], b"$OV_PRO");
+}
+
+// Tests that a write request carries both the name and the value.
+#[test]
+fn test_encode_write_request() {
+    let frame = codec::encode(&Request::write(1, "$OV_PRO", "50")).unwrap();
+
+    assert_eq!(&frame[..4], &[0, 1, 0, 14]);
+    assert_eq!(frame[4], 1);
+    assert_eq!(&frame[14..], &[0, 2, b'5', b'0']);
+}
+
+// Tests that requests survive an encode/decode round trip.
+#[test]
+fn test_request_round_trip() {
+    let request = Request::write(42, "$OUT[3]", "TRUE");
+    assert_eq!(
+        codec::decode_request(&codec::encode(&request).unwrap()).unwrap(),
+        request
+    );
+
+    let request = Request::read(43, "$POS_ACT");
+    assert_eq!(
+        codec::decode_request(&codec::encode(&request).unwrap()).unwrap(),
+        request
+    );
+}
+
+// Tests decoding a response frame including its status trailer.
+#[test]
+fn test_decode_response() {
+    let frame = [0, 9, 0, 9, 0, 0, 4, b'T', b'R', b'U', b'E', 0, 1];
+    let response = codec::decode(&frame).unwrap();
+
+    assert_eq!(
+        response,
+        Response {
+            msg_id: 9,
+            mode: Mode::Read,
+            value: "TRUE".to_string(),
+            success: true,
+        }
+    );
+}
+
+// Tests that the failure trailer is reported and unknown trailers are rejected.
+#[test]
+fn test_decode_response_status() {
+    // Failure trailer
+    let response = codec::decode(&[0, 1, 0, 5, 0, 0, 0, 0, 0]).unwrap();
+    assert!(!response.success);
+    assert_eq!(response.value, "");
+
+    // Missing and unknown trailers
+    assert!(codec::decode(&[0, 1, 0, 3, 0, 0, 0]).is_err());
+    assert!(codec::decode(&[0, 1, 0, 5, 0, 0, 0, 1, 1]).is_err());
+}
+
+// Tests that truncated and malformed frames are rejected.
+#[test]
+fn test_decode_invalid_frames() {
+    // Incomplete header
+    assert!(codec::decode(&[0, 1]).is_err());
+    // Body shorter than announced in the header
+    assert!(codec::decode(&[0, 1, 0, 9, 0, 0, 4, b'T']).is_err());
+    // Value shorter than its length field
+    assert!(codec::decode(&[0, 1, 0, 4, 0, 0, 4, b'T']).is_err());
+    // Unknown mode byte
+    assert!(codec::decode(&[0, 1, 0, 5, 7, 0, 0, 0, 1]).is_err());
+}
+
+// Tests splitting a byte stream into frames using the header.
+#[test]
+fn test_frame_len() {
+    let mut stream = codec::encode(&Request::read(1, "$OV_PRO")).unwrap();
+    stream.extend(codec::encode(&Request::read(2, "$MODE_OP")).unwrap());
+
+    let first = codec::frame_len(&stream).unwrap();
+    assert_eq!(
+        codec::decode_request(&stream[..first]).unwrap().name,
+        "$OV_PRO"
+    );
+    assert_eq!(
+        codec::decode_request(&stream[first..]).unwrap().name,
+        "$MODE_OP"
+    );
+}
+
+// Tests the protocol and KRL limits enforced on variable names.
+#[test]
+fn test_validate_name() {
+    assert!(codec::validate_name("$POS_ACT").is_ok());
+    assert!(codec::validate_name("PARTS[20,3].ID").is_ok());
+
+    // Empty names, control characters and overlong identifiers are rejected
+    assert!(codec::validate_name("").is_err());
+    assert!(codec::validate_name("$OV_PRO\n").is_err());
+    assert!(codec::validate_name("$TOOL_DATA[1].A_MEMBER_NAME_THAT_IS_TOO_LONG").is_err());
+}
+
+// Tests that values which do not fit into a frame are rejected.
+#[test]
+fn test_encode_value_too_long() {
+    let max = codec::MAX_BODY_LEN - 5 - "$OV_PRO".len();
+
+    let request = Request::write(1, "$OV_PRO", &"1".repeat(max));
+    assert_eq!(
+        codec::encode(&request).unwrap().len(),
+        4 + codec::MAX_BODY_LEN
+    );
+
+    let request = Request::write(1, "$OV_PRO", &"1".repeat(max + 1));
+    assert!(matches!(
+        codec::encode(&request),
+        Err(Error::ValueTooLong { len, max: limit }) if len == max + 1 && limit == max
+    ));
+}
+
+// Tests that names which do not fit into a frame are rejected for reads and writes.
+#[test]
+fn test_encode_name_too_long() {
+    // Every identifier is short, but the name as a whole exceeds the body
+    let name = format!("A{}", ".A".repeat(codec::MAX_BODY_LEN / 2));
+    assert!(codec::validate_name(&name).is_ok());
+    assert!(matches!(
+        codec::encode(&Request::read(1, &name)),
+        Err(Error::InvalidName { .. })
+    ));
+    assert!(matches!(
+        codec::encode(&Request::write(1, &name, "1")),
+        Err(Error::InvalidName { .. })
+    ));
+
+    // A name filling the body exactly still fits into a read, but leaves no room for a write
+    let name = format!("AB{}", ".A".repeat((codec::MAX_BODY_LEN - 5) / 2));
+    assert_eq!(name.len(), codec::MAX_BODY_LEN - 3);
+    assert_eq!(
+        codec::encode(&Request::read(1, &name)).unwrap().len(),
+        4 + codec::MAX_BODY_LEN
+    );
+    assert!(matches!(
+        codec::encode(&Request::write(1, &name, "")),
+        Err(Error::InvalidName { .. })
+    ));
+}
@@ -0,0 +1,86 @@
+use rs_openshowvar::krl::Frame;
+use rs_openshowvar::{Error, FromKrl, KrlEnum, KrlStruct, KrlValue, ToKrl};
+
+#[derive(Debug, Clone, Copy, PartialEq, KrlEnum)]
+enum PartState {
+    Waiting,
+    #[krl(rename = "IN_WORK")]
+    Processing,
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, KrlStruct)]
+#[krl(rename = "PART_T")]
+struct Part {
+    id: i32,
+    #[krl(rename = "NAME")]
+    label: String,
+    state: PartState,
+    offset: Frame,
+    weight: Option<f64>,
+}
+
+// Tests that a derived struct is read from a KRL structure literal.
+#[test]
+fn test_derive_from_krl() {
+    let value = KrlValue::parse(
+        "{PART_T: ID 7, NAME[] \"bolt\", STATE #IN_WORK, OFFSET {FRAME: X 1.0, Y 0.0, Z 2.0, A 0.0, B 0.0, C 0.0}}",
+    )
+    .unwrap();
+    let part = Part::from_krl(&value).unwrap();
+    assert_eq!(part.id, 7);
+    assert_eq!(part.label, "bolt");
+    assert_eq!(part.state, PartState::Processing);
+    assert_eq!(part.offset.z, 2.0);
+    assert_eq!(part.weight, None);
+
+    // Unknown enum values and missing members are reported
+    assert!(matches!(
+        PartState::from_krl(&KrlValue::Enum("LOST".to_string())),
+        Err(Error::UnexpectedType {
+            expected: "PARTSTATE",
+            ..
+        })
+    ));
+    assert!(matches!(
+        Part::from_krl(&KrlValue::parse("{ID 7}").unwrap()),
+        Err(Error::MissingMember { .. })
+    ));
+}
+
+// Tests that a derived struct is written as a KRL structure literal.
+#[test]
+fn test_derive_to_krl() {
+    let part = Part {
+        id: 3,
+        label: "nut".to_string(),
+        state: PartState::Done,
+        offset: Frame::default(),
+        weight: Some(0.5),
+    };
+    let text = part.to_krl_value().unwrap().unwrap().to_krl().unwrap();
+    assert_eq!(
+        text,
+        "{PART_T: ID 3, NAME[] \"nut\", STATE #DONE, OFFSET {FRAME: X 0.0, Y 0.0, Z 0.0, A 0.0, B 0.0, C 0.0}, WEIGHT 0.5}"
+    );
+    assert_eq!(
+        Part::from_krl(&KrlValue::parse(&text).unwrap()).unwrap(),
+        part
+    );
+
+    // Members set to `None` are left out for partial writes
+    let partial = Part {
+        weight: None,
+        ..part
+    };
+    assert!(!partial
+        .to_krl_value()
+        .unwrap()
+        .unwrap()
+        .to_string()
+        .contains("WEIGHT"));
+    assert_eq!(
+        PartState::Waiting.to_krl_value().unwrap(),
+        Some(KrlValue::Enum("WAITING".to_string()))
+    );
+}
@@ -0,0 +1,338 @@
+use rs_openshowvar::krl::{Axis, E6Axis, E6Pos, Frame, Inertia, Load, ParseError, Pos};
+use rs_openshowvar::{Error, FromKrl, KrlValue, ToKrl};
+
+// Tests that scalar literals are parsed into the matching variant.
+#[test]
+fn test_parse_scalars() {
+    assert_eq!(KrlValue::parse("42").unwrap(), KrlValue::Int(42));
+    assert_eq!(KrlValue::parse(" -7 ").unwrap(), KrlValue::Int(-7));
+    assert_eq!(KrlValue::parse("1.5").unwrap(), KrlValue::Real(1.5));
+    assert_eq!(KrlValue::parse("-2.5E-3").unwrap(), KrlValue::Real(-0.0025));
+    assert_eq!(KrlValue::parse(".5").unwrap(), KrlValue::Real(0.5));
+    assert_eq!(KrlValue::parse("1E3").unwrap(), KrlValue::Real(1000.0));
+    assert_eq!(KrlValue::parse("TRUE").unwrap(), KrlValue::Bool(true));
+    assert_eq!(KrlValue::parse("false").unwrap(), KrlValue::Bool(false));
+    assert_eq!(
+        KrlValue::parse("\"Hello, {world}\"").unwrap(),
+        KrlValue::String("Hello, {world}".to_string())
+    );
+    assert_eq!(
+        KrlValue::parse("#T1").unwrap(),
+        KrlValue::Enum("T1".to_string())
+    );
+    assert_eq!(
+        KrlValue::parse("'B0101'").unwrap(),
+        KrlValue::Bitfield(vec![false, true, false, true])
+    );
+    assert_eq!(
+        KrlValue::parse("'H1A'").unwrap().as_bits().unwrap(),
+        &[false, false, false, true, true, false, true, false]
+    );
+    assert_eq!(KrlValue::parse("\"A\"").unwrap().as_char(), Some('A'));
+}
+
+// Tests that a structure as returned for `$POS_ACT` is parsed with its members.
+#[test]
+fn test_parse_struct() {
+    let value = KrlValue::parse(
+        "{E6POS: X 100.0, Y 0.0, Z 500.0, A 0.0, B 90.0, C 0.0, S 2, T 10, E1 0.0, E2 0.0}",
+    )
+    .unwrap();
+    assert_eq!(value.type_name(), Some("E6POS"));
+    assert_eq!(value.fields().unwrap().len(), 10);
+    assert_eq!(value.field("x").and_then(KrlValue::as_real), Some(100.0));
+    assert_eq!(value.field("S").and_then(KrlValue::as_int), Some(2));
+    assert_eq!(value.field("E2").and_then(KrlValue::as_real), Some(0.0));
+    assert!(value.field("E7").is_none());
+}
+
+// Tests nested structures, string members and structures without a type name.
+#[test]
+fn test_parse_nested_struct() {
+    let value = KrlValue::parse(
+        "{LOAD: M 5.0, CM {FRAME: X 1.0, Y 2.0, Z 3.0, A 0.0, B 0.0, C 0.0}, NAME[] \"gripper\", MODE #ACTIVE, OK TRUE}",
+    )
+    .unwrap();
+    let cm = value.field("CM").unwrap();
+    assert_eq!(cm.type_name(), Some("FRAME"));
+    assert_eq!(cm.field("Z").and_then(KrlValue::as_real), Some(3.0));
+    assert_eq!(
+        value.field("NAME").and_then(KrlValue::as_str),
+        Some("gripper")
+    );
+    assert_eq!(
+        value.field("MODE").and_then(KrlValue::as_enum),
+        Some("ACTIVE")
+    );
+    assert_eq!(value.field("OK").and_then(KrlValue::as_bool), Some(true));
+
+    let partial: KrlValue = "{X 10.0, Y 20.0}".parse().unwrap();
+    assert_eq!(partial.type_name(), None);
+    assert_eq!(partial.fields().unwrap().len(), 2);
+    assert_eq!(
+        KrlValue::parse("{}").unwrap(),
+        KrlValue::Struct {
+            type_name: None,
+            fields: Vec::new()
+        }
+    );
+}
+
+// Tests that invalid literals report the position of the problem.
+#[test]
+fn test_parse_error_positions() {
+    let err = |input: &str| KrlValue::parse(input).unwrap_err();
+
+    assert_eq!(err("").position, 0);
+    assert_eq!(err("12 34").position, 3);
+    assert_eq!(err("{X 1.0 Y 2.0}").position, 7);
+    assert_eq!(err("{X 1.0, Y }").position, 10);
+    assert_eq!(err("\"open").position, 0);
+    assert_eq!(err("'B012'").position, 4);
+    assert_eq!(err("99999999999").reason, "INT out of range");
+    assert_eq!(err("1.5E").position, 4);
+    assert_eq!(
+        err("MAYBE"),
+        ParseError {
+            position: 0,
+            reason: "Unknown identifier, expected TRUE or FALSE"
+        }
+    );
+
+    // Parse errors convert into the crate error type
+    let error: Error = err("{X ").into();
+    assert_eq!(
+        error.to_string(),
+        "Invalid KRL value: Unexpected end of input at position 3"
+    );
+}
+
+// Tests that deeply nested structures are rejected instead of overflowing the stack.
+#[test]
+fn test_parse_nesting_limit() {
+    let nested = |depth: usize| format!("{}1{}", "{A ".repeat(depth), "}".repeat(depth));
+
+    assert!(KrlValue::parse(&nested(32)).is_ok());
+    assert_eq!(
+        KrlValue::parse(&nested(33)).unwrap_err(),
+        ParseError {
+            position: 96,
+            reason: "Structures nested too deeply"
+        }
+    );
+
+    // A value of about 40 KB fits in one frame and must not abort a small thread
+    let handle = std::thread::Builder::new()
+        .stack_size(256 * 1024)
+        .spawn(move || KrlValue::parse(&nested(13_000)).is_err())
+        .unwrap();
+    assert!(handle.join().unwrap());
+}
+
+// Tests that values are formatted in canonical KRL syntax.
+#[test]
+fn test_serialize_scalars() {
+    assert_eq!(KrlValue::from(42).to_krl().unwrap(), "42");
+    assert_eq!(KrlValue::from(100.0).to_krl().unwrap(), "100.0");
+    assert_eq!(KrlValue::from(-0.25).to_krl().unwrap(), "-0.25");
+    assert_eq!(KrlValue::from(0.1f32).to_krl().unwrap(), "0.1");
+    assert_eq!(KrlValue::from(1e20).to_krl().unwrap(), "1.0E20");
+    assert_eq!(KrlValue::from(1.5e-9).to_krl().unwrap(), "1.5E-9");
+    assert_eq!(KrlValue::from(true).to_krl().unwrap(), "TRUE");
+    assert_eq!(KrlValue::from('A').to_krl().unwrap(), "\"A\"");
+    assert_eq!(KrlValue::from("Program").to_krl().unwrap(), "\"Program\"");
+    assert_eq!(KrlValue::Enum("T1".to_string()).to_krl().unwrap(), "#T1");
+    assert_eq!(
+        KrlValue::Bitfield(vec![true, false, true])
+            .to_krl()
+            .unwrap(),
+        "'B101'"
+    );
+}
+
+// Tests that structures and partial structures are formatted with their members.
+#[test]
+fn test_serialize_struct() {
+    let load = KrlValue::Struct {
+        type_name: Some("LOAD".to_string()),
+        fields: vec![
+            ("M".to_string(), KrlValue::from(5.0)),
+            (
+                "CM".to_string(),
+                KrlValue::partial([("X", KrlValue::from(1.0)), ("Z", KrlValue::from(3))]),
+            ),
+            ("NAME".to_string(), KrlValue::from("gripper")),
+            ("MODE".to_string(), KrlValue::Enum("ACTIVE".to_string())),
+        ],
+    };
+    let text = load.to_krl().unwrap();
+    assert_eq!(
+        text,
+        "{LOAD: M 5.0, CM {X 1.0, Z 3}, NAME[] \"gripper\", MODE #ACTIVE}"
+    );
+
+    // The formatted literal parses back into the same value
+    assert_eq!(KrlValue::parse(&text).unwrap(), load);
+}
+
+// Tests that values the controller cannot read back are rejected.
+#[test]
+fn test_serialize_invalid() {
+    let invalid = |value: KrlValue| matches!(value.to_krl(), Err(Error::InvalidInput(_)));
+
+    assert!(invalid(KrlValue::from("say \"hi\"")));
+    assert!(invalid(KrlValue::from("line\nbreak")));
+    assert!(invalid(KrlValue::from(f64::NAN)));
+    assert!(invalid(KrlValue::from(f64::INFINITY)));
+    assert!(invalid(KrlValue::Enum("T 1".to_string())));
+    assert!(invalid(KrlValue::Bitfield(Vec::new())));
+    assert!(invalid(KrlValue::partial([("1X", KrlValue::from(1))])));
+    assert!(invalid(KrlValue::partial([(
+        "X",
+        KrlValue::from(f64::NAN)
+    )])));
+}
+
+// Tests the conversions between `KrlValue` and Rust types.
+#[test]
+fn test_convert() {
+    assert_eq!(u8::from_krl(&KrlValue::Int(200)).unwrap(), 200);
+    assert!(matches!(
+        u8::from_krl(&KrlValue::Int(300)),
+        Err(Error::UnexpectedType { expected: "u8", .. })
+    ));
+    assert_eq!(f64::from_krl(&KrlValue::Int(3)).unwrap(), 3.0);
+    assert_eq!(char::from_krl(&KrlValue::from("x")).unwrap(), 'x');
+    assert!(bool::from_krl(&KrlValue::Int(1)).is_err());
+
+    // Members are looked up by name, and optional members may be missing
+    let value = KrlValue::parse("{POS: X 1.0, S 6}").unwrap();
+    assert_eq!(value.get::<f64>("x").unwrap(), 1.0);
+    assert_eq!(value.get::<Option<i32>>("T").unwrap(), None);
+    assert!(matches!(
+        value.get::<i32>("T"),
+        Err(Error::MissingMember { .. })
+    ));
+    assert!(<(f64, i32, i32)>::from_krl(&value).is_err());
+
+    assert_eq!(Some(5).to_krl_value().unwrap(), Some(KrlValue::Int(5)));
+    assert_eq!("text".to_krl_value().unwrap(), Some(KrlValue::from("text")));
+}
+
+// Tests that bit patterns, which are INT literals in KRL, convert to integers.
+#[test]
+fn test_convert_bit_pattern_to_int() {
+    let int = |input: &str| i32::from_krl(&KrlValue::parse(input).unwrap());
+
+    assert_eq!(int("'B0101'").unwrap(), 5);
+    assert_eq!(int("'H1F'").unwrap(), 31);
+    assert_eq!(int("'H0000000000FF'").unwrap(), 255);
+    assert_eq!(int("'HFFFFFFFF'").unwrap(), -1);
+    assert_eq!(u8::from_krl(&KrlValue::parse("'B1'").unwrap()).unwrap(), 1);
+    assert!(matches!(
+        int("'H100000000'"),
+        Err(Error::UnexpectedType { .. })
+    ));
+
+    // REAL targets widen bit patterns like any other INT
+    assert_eq!(KrlValue::parse("'H1F'").unwrap().as_real(), Some(31.0));
+    assert_eq!(
+        f64::from_krl(&KrlValue::parse("'B11'").unwrap()).unwrap(),
+        3.0
+    );
+}
+
+// Tests that integers are written as KRL INTs only when they fit into 32 bits.
+#[test]
+fn test_convert_integer_range() {
+    assert_eq!((-5i64).to_krl_value().unwrap(), Some(KrlValue::Int(-5)));
+    assert_eq!(
+        u32::try_from(i32::MAX).unwrap().to_krl_value().unwrap(),
+        Some(KrlValue::Int(i32::MAX))
+    );
+    assert_eq!(7u64.to_krl_value().unwrap(), Some(KrlValue::Int(7)));
+
+    assert!(matches!(
+        (i64::from(i32::MIN) - 1).to_krl_value(),
+        Err(Error::Conversion(_))
+    ));
+    assert!(matches!(
+        (u32::MAX).to_krl_value(),
+        Err(Error::Conversion(_))
+    ));
+    assert!(matches!(u64::MAX.to_krl_value(), Err(Error::Conversion(_))));
+}
+
+// Tests reading the standard structures from the literals returned by the controller.
+#[test]
+fn test_geometry_from_krl() {
+    let pos: E6Pos = "{E6POS: X 100.0, Y -20.5, Z 500.0, A 0.0, B 90.0, C 0.0, S 2, T 10, E1 0.0, E2 0.0, E3 0.0, E4 0.0, E5 0.0, E6 0.0}"
+        .parse()
+        .unwrap();
+    assert_eq!(pos.y, -20.5);
+    assert_eq!((pos.s, pos.t), (Some(2), Some(10)));
+
+    let axis: E6Axis = "{E6AXIS: A1 0.0, A2 -90.0, A3 90.0, A4 0.0, A5 45.0, A6 0.0, E1 0.0, E2 0.0, E3 0.0, E4 0.0, E5 0.0, E6 0.0}"
+        .parse()
+        .unwrap();
+    assert_eq!(axis.a2, -90.0);
+
+    // A frame read back into `Pos` has no status and turn
+    let pos: Pos = "{FRAME: X 1.0, Y 2.0, Z 3.0, A 0.0, B 0.0, C 0.0}"
+        .parse()
+        .unwrap();
+    assert_eq!(pos.s, None);
+
+    let load: Load = "{LOAD: M 12.5, CM {FRAME: X 0.0, Y 0.0, Z 80.0, A 0.0, B 0.0, C 0.0}, J {INERTIA: X 0.1, Y 0.1, Z 0.2}}"
+        .parse()
+        .unwrap();
+    assert_eq!(load.cm.z, 80.0);
+    assert_eq!(load.j.z, 0.2);
+
+    // Missing members and other types are reported
+    assert!(matches!(
+        "{AXIS: A1 0.0}".parse::<Axis>(),
+        Err(Error::MissingMember { .. })
+    ));
+    assert!(matches!(
+        "42".parse::<Frame>(),
+        Err(Error::UnexpectedType {
+            expected: "FRAME",
+            ..
+        })
+    ));
+}
+
+// Tests formatting the standard structures as KRL literals.
+#[test]
+fn test_geometry_to_krl() {
+    let tool = Frame {
+        z: 150.0,
+        ..Frame::default()
+    };
+    assert_eq!(
+        tool.to_string(),
+        "{FRAME: X 0.0, Y 0.0, Z 150.0, A 0.0, B 0.0, C 0.0}"
+    );
+
+    let pos = Pos {
+        x: 10.0,
+        s: Some(6),
+        ..Pos::default()
+    };
+    assert_eq!(
+        pos.to_string(),
+        "{POS: X 10.0, Y 0.0, Z 0.0, A 0.0, B 0.0, C 0.0, S 6}"
+    );
+
+    let load = Load {
+        m: 5.0,
+        ..Load::default()
+    };
+    assert_eq!(load.to_string().parse::<Load>().unwrap(), load);
+    let inertia = Inertia::default();
+    assert_eq!(
+        KrlValue::from(inertia).to_krl().unwrap(),
+        "{INERTIA: X 0.0, Y 0.0, Z 0.0}"
+    );
+}
@@ -0,0 +1,154 @@
+use rs_openshowvar::krl;
+use rs_openshowvar::{Error, KrlValue, OpenShowVar};
+use serde::{Deserialize, Serialize};
+
+use super::{start_mock_server, start_scripted_server};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Mode {
+    #[serde(rename = "T1")]
+    Manual,
+    #[serde(rename = "AUT")]
+    Automatic,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Part {
+    id: u16,
+    name: String,
+    mode: Mode,
+    offset: Offset,
+    weight: Option<f64>,
+    #[serde(default)]
+    flags: Vec<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Offset {
+    x: f64,
+    y: f64,
+}
+
+// Tests deserializing a serde type from a KRL structure literal.
+#[test]
+fn test_serde_from_str() {
+    let part: Part = krl::from_str(
+        "{PART_T: ID 7, NAME[] \"bolt\", MODE #t1, OFFSET {X 1.5, Y -2.0}, FLAGS 'B101', EXTRA 1}",
+    )
+    .unwrap();
+    assert_eq!(
+        part,
+        Part {
+            id: 7,
+            name: "bolt".to_string(),
+            mode: Mode::Manual,
+            offset: Offset { x: 1.5, y: -2.0 },
+            weight: None,
+            flags: vec![true, false, true],
+        }
+    );
+
+    // Tuples read structure members in order
+    let (x, y): (f64, f64) = krl::from_str("{X 1.0, Y 2}").unwrap();
+    assert_eq!((x, y), (1.0, 2.0));
+
+    // Type mismatches and missing members are reported
+    assert!(krl::from_str::<Offset>("{X 1.0}").is_err());
+    assert!(krl::from_str::<u8>("300").is_err());
+    assert!(matches!(
+        krl::from_str::<Mode>("#EXT"),
+        Err(Error::Conversion(_))
+    ));
+    assert!(matches!(
+        krl::from_str::<Offset>("{X 1.0"),
+        Err(Error::Parse(_))
+    ));
+}
+
+// Tests that bit patterns deserialize into numbers the same way `FromKrl` converts them.
+#[test]
+fn test_serde_bit_pattern_to_int() {
+    assert_eq!(krl::from_str::<i32>("'H1F'").unwrap(), 31);
+    assert_eq!(krl::from_str::<u8>("'B0101'").unwrap(), 5);
+    assert_eq!(krl::from_str::<f64>("'B11'").unwrap(), 3.0);
+    assert_eq!(krl::from_str::<i32>("'HFFFFFFFF'").unwrap(), -1);
+    assert!(krl::from_str::<i32>("'H100000000'").is_err());
+    assert!(krl::from_str::<u8>("'H1FF'").is_err());
+
+    // Sequences of flags still take the bits one by one
+    assert_eq!(
+        krl::from_str::<Vec<bool>>("'B10'").unwrap(),
+        vec![true, false]
+    );
+}
+
+// Tests serializing a serde type as a KRL literal.
+#[test]
+fn test_serde_to_string() {
+    let part = Part {
+        id: 3,
+        name: "nut".to_string(),
+        mode: Mode::Automatic,
+        offset: Offset { x: 10.0, y: 0.5 },
+        weight: None,
+        flags: vec![false, true],
+    };
+    let text = krl::to_string(&part).unwrap();
+    assert_eq!(
+        text,
+        "{ID 3, NAME[] \"nut\", MODE #AUT, OFFSET {X 10.0, Y 0.5}, FLAGS 'B01'}"
+    );
+    assert_eq!(krl::from_str::<Part>(&text).unwrap(), part);
+
+    assert_eq!(krl::to_string(&true).unwrap(), "TRUE");
+    assert_eq!(krl::to_value(&2.0f32).unwrap(), KrlValue::Real(2.0));
+    assert!(matches!(
+        krl::to_string(&None::<i32>),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        krl::to_string(&u32::MAX),
+        Err(Error::Conversion(_))
+    ));
+    assert!(krl::to_string(&vec![1, 2]).is_err());
+    assert!(matches!(
+        krl::to_string(&Vec::<bool>::new()),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+// Tests round-tripping a KRL structure through JSON.
+#[test]
+fn test_serde_json_round_trip() {
+    let part: Part =
+        krl::from_str("{ID 1, NAME[] \"pin\", MODE #AUT, OFFSET {X 0.0, Y 4.0}, WEIGHT 0.25}")
+            .unwrap();
+    let json = serde_json::to_string(&part).unwrap();
+    let from_json: Part = serde_json::from_str(&json).unwrap();
+    assert_eq!(from_json, part);
+    assert_eq!(
+        krl::to_string(&from_json).unwrap(),
+        "{ID 1, NAME[] \"pin\", MODE #AUT, OFFSET {X 0.0, Y 4.0}, WEIGHT 0.25}"
+    );
+}
+
+// Tests reading and writing serde types through `OpenShowVar`.
+#[test]
+fn test_serde_read_write() {
+    // Start a mock server answering with a frame
+    let listener = start_scripted_server(&["{FRAME: X 1.0, Y 2.0, Z 3.0, A 0.0, B 0.0, C 0.0}"]);
+    let addr = listener.local_addr().unwrap();
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+    let offset: Offset = krl::from_str(&osv.read("$TOOL").unwrap()).unwrap();
+    assert_eq!(offset, Offset { x: 1.0, y: 2.0 });
+
+    // Start the mock server, which echoes written values
+
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+    let text = krl::to_string(&offset).unwrap();
+    assert_eq!(osv.write("$TOOL", &text).unwrap(), "{X 1.0, Y 2.0}");
+}
@@ -1,64 +1,308 @@
-use rs_openshowvar::OpenShowVar;
+#[cfg(feature = "tokio")]
+mod async_client;
+mod codec;
+#[cfg(feature = "derive")]
+mod derive;
+mod krl;
+#[cfg(feature = "serde")]
+mod serde;
+
+use rs_openshowvar::codec::{decode_request, encode_response, Mode, Response};
+use rs_openshowvar::{
+    ConnectionState, Error, KrlValue, OpenShowVar, OpenShowVarPool, OperatingMode, ProgramState,
+    ReconnectPolicy, SystemVar, Watch,
+};
 use std::io::{Read, Write};
-use std::net::TcpListener;
+use std::net::{TcpListener, TcpStream};
 use std::thread;
+use std::time::Duration;
 
 // Helper function to start a mock server.
 //
 // This function contains the necessary code to start a TCP server for use in tests.
 // It returns a TCP listener and a thread handle that manages the server.
 // Instead of a real server, a simulated server is created for use in tests.
-// Details of the server implementation are not covered here.
+// Each connection is served on its own thread until the client disconnects.
 fn start_mock_server() -> (TcpListener, std::thread::JoinHandle<()>) {
+    start_flaky_server(0)
+}
+
+// Helper function to start a mock server that drops its first connections.
+//
+// The first `drops` connections are closed right after being accepted,
+// simulating a proxy restart. Later connections are served normally.
+fn start_flaky_server(drops: usize) -> (TcpListener, std::thread::JoinHandle<()>) {
     // Bind a TCP listener to a random port on localhost
     let listener = TcpListener::bind("127.0.0.1:0").unwrap();
     // Spawn a thread to handle incoming connections
     let handle = thread::spawn({
         let listener = listener.try_clone().unwrap();
         move || {
-            for stream in listener.incoming() {
-                let mut stream = stream.unwrap();
-                let mut buffer = [0; 1024];
-                let n = stream.read(&mut buffer).unwrap();
-                // Process the request and send back the appropriate response
-                let response = process_request(&buffer[..n]);
-                stream.write_all(&response).unwrap();
+            for stream in listener.incoming().skip(drops) {
+                let stream = stream.unwrap();
+                thread::spawn(move || serve_connection(stream));
             }
         }
     });
     (listener, handle)
 }
 
+// Answers framed requests on a mock server connection until the client disconnects.
+fn serve_connection(mut stream: TcpStream) {
+    loop {
+        let mut header = [0; 4];
+        if stream.read_exact(&mut header).is_err() {
+            return;
+        }
+        let msg_len = ((header[2] as usize) << 8) | (header[3] as usize);
+        let mut request = header.to_vec();
+        request.resize(4 + msg_len, 0);
+        if stream.read_exact(&mut request[4..]).is_err() {
+            return;
+        }
+        // Process the request and send back the appropriate response
+        let response = process_request(&request);
+        if stream.write_all(&response).is_err() {
+            return;
+        }
+    }
+}
+
 // Processes a mock request and generates the appropriate response.
 //
-// This function is responsible for processing a mock request and generating
-// the appropriate response. In this implementation, the request is simply echoed back.
+// Read requests are answered with the variable name as the value and write
+// requests with the written value. Requests for `non_existing_var` are
+// answered with the failure status trailer.
 fn process_request(request: &[u8]) -> Vec<u8> {
-    // Here we should process the request and generate the appropriate response.
-    // For simplicity, we will just echo back the request.
-    let response = request.to_vec();
-
-    // Modify the response to match the expected behavior in tests
-    if request[4] == 1 {
-        // For write requests, echo only the value part with the necessary header
-        let var_name_len = ((request[5] as usize) << 8) | (request[6] as usize);
-        let val_len =
-            ((request[7 + var_name_len] as usize) << 8) | (request[7 + var_name_len + 1] as usize);
-        let mut response = vec![
-            0,
-            0,
-            0,
-            (3 + val_len) as u8,
-            1,
-            (val_len >> 8) as u8,
-            (val_len & 0xFF) as u8,
-        ];
-        response.extend_from_slice(&request[7 + var_name_len + 2..]);
-        response
-    } else {
-        // For read requests, echo the entire request
-        response
-    }
+    let request = decode_request(request).unwrap();
+    let success = request.name != "non_existing_var";
+    let value = match (success, request.mode) {
+        (false, _) => String::new(),
+        (true, Mode::Read) => request.name,
+        (true, Mode::Write) => request.value,
+    };
+    encode_response(&Response {
+        msg_id: request.msg_id,
+        mode: request.mode,
+        value,
+        success,
+    })
+    .unwrap()
+}
+
+// Helper function to start a mock server that replies with a fixed frame.
+//
+// The server reads a single request and writes the given response back in
+// small chunks with short pauses in between, simulating a reply that arrives
+// fragmented over several TCP segments.
+fn start_chunked_server(response: Vec<u8>, chunk_size: usize) -> TcpListener {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    thread::spawn({
+        let listener = listener.try_clone().unwrap();
+        move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0; 1024];
+            let _ = stream.read(&mut buffer).unwrap();
+            for chunk in response.chunks(chunk_size) {
+                stream.write_all(chunk).unwrap();
+                stream.flush().unwrap();
+                thread::sleep(Duration::from_millis(5));
+            }
+        }
+    });
+    listener
+}
+
+// Helper function to start a mock server that resets its connection shortly after accepting it.
+//
+// The server waits 100 ms, so the client has finished connecting, and then
+// closes the connection without reading any request.
+fn start_resetting_server() -> TcpListener {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    thread::spawn({
+        let listener = listener.try_clone().unwrap();
+        move || {
+            let (stream, _) = listener.accept().unwrap();
+            thread::sleep(Duration::from_millis(100));
+            // Closing with a zero linger time sends a reset instead of a FIN
+            socket2::SockRef::from(&stream)
+                .set_linger(Some(Duration::ZERO))
+                .unwrap();
+        }
+    });
+    listener
+}
+
+// Helper function to start a mock server that stalls in the middle of its first reply.
+//
+// The server sends the first 6 bytes of its first response, pauses for
+// `stall` and then sends the rest. Later requests, including those on new
+// connections, are answered at once.
+fn start_stalling_server(stall: Duration) -> TcpListener {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    thread::spawn({
+        let listener = listener.try_clone().unwrap();
+        move || {
+            let mut first = true;
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                loop {
+                    let mut header = [0; 4];
+                    if stream.read_exact(&mut header).is_err() {
+                        break;
+                    }
+                    let msg_len = ((header[2] as usize) << 8) | (header[3] as usize);
+                    let mut request = header.to_vec();
+                    request.resize(4 + msg_len, 0);
+                    if stream.read_exact(&mut request[4..]).is_err() {
+                        break;
+                    }
+                    let response = process_request(&request);
+                    if first {
+                        first = false;
+                        stream.write_all(&response[..6]).unwrap();
+                        thread::sleep(stall);
+                        let _ = stream.write_all(&response[6..]);
+                    } else if stream.write_all(&response).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    listener
+}
+
+// Helper function to start a mock server that drops its first connection mid-session.
+//
+// The server answers `answers` reads on the first connection with "1" and
+// then closes it, as a restarting proxy would. Reads on later connections
+// are answered with "2".
+fn start_restarting_server(answers: usize) -> TcpListener {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    thread::spawn({
+        let listener = listener.try_clone().unwrap();
+        move || {
+            for (connection, stream) in listener.incoming().enumerate() {
+                let mut stream = stream.unwrap();
+                let (value, limit) = match connection {
+                    0 => ("1", answers),
+                    _ => ("2", usize::MAX),
+                };
+                thread::spawn(move || {
+                    for _ in 0..limit {
+                        let mut header = [0; 4];
+                        if stream.read_exact(&mut header).is_err() {
+                            return;
+                        }
+                        let msg_len = ((header[2] as usize) << 8) | (header[3] as usize);
+                        let mut request = header.to_vec();
+                        request.resize(4 + msg_len, 0);
+                        if stream.read_exact(&mut request[4..]).is_err() {
+                            return;
+                        }
+                        let request = decode_request(&request).unwrap();
+                        let response = encode_response(&Response {
+                            msg_id: request.msg_id,
+                            mode: request.mode,
+                            value: value.to_string(),
+                            success: true,
+                        })
+                        .unwrap();
+                        if stream.write_all(&response).is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        }
+    });
+    listener
+}
+
+// Helper function to start a mock server that answers reads with fixed values.
+//
+// The server answers the requests on its first connection in order, each
+// with the next value from `values`, and writes with the written value.
+fn start_scripted_server(values: &[&str]) -> TcpListener {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    thread::spawn({
+        let listener = listener.try_clone().unwrap();
+        move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut values = values.into_iter();
+            loop {
+                let mut header = [0; 4];
+                if stream.read_exact(&mut header).is_err() {
+                    return;
+                }
+                let msg_len = ((header[2] as usize) << 8) | (header[3] as usize);
+                let mut request = header.to_vec();
+                request.resize(4 + msg_len, 0);
+                stream.read_exact(&mut request[4..]).unwrap();
+                let request = decode_request(&request).unwrap();
+                let value = match request.mode {
+                    Mode::Read => values.next().unwrap_or_default(),
+                    Mode::Write => request.value,
+                };
+                let response = encode_response(&Response {
+                    msg_id: request.msg_id,
+                    mode: request.mode,
+                    value,
+                    success: true,
+                })
+                .unwrap();
+                stream.write_all(&response).unwrap();
+            }
+        }
+    });
+    listener
+}
+
+// Helper function to start a mock server that answers pipelined reads in reverse order.
+//
+// The server reads `count` framed read requests and then answers all of them,
+// last request first, with the variable name as the value.
+fn start_reversing_server(count: usize) -> TcpListener {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    thread::spawn({
+        let listener = listener.try_clone().unwrap();
+        move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut replies = Vec::new();
+            for _ in 0..count {
+                let mut header = [0; 4];
+                stream.read_exact(&mut header).unwrap();
+                let msg_len = ((header[2] as usize) << 8) | (header[3] as usize);
+                let mut body = vec![0; msg_len];
+                stream.read_exact(&mut body).unwrap();
+                let msg_id = ((header[0] as u16) << 8) | (header[1] as u16);
+                replies.push(read_response(msg_id, &body[3..]));
+            }
+            for reply in replies.iter().rev() {
+                stream.write_all(reply).unwrap();
+            }
+        }
+    });
+    listener
+}
+
+// Builds a read response frame with the given message ID carrying the given value.
+fn read_response(msg_id: u16, value: &[u8]) -> Vec<u8> {
+    let msg_len = 3 + value.len() + 2;
+    let mut response = vec![
+        (msg_id >> 8) as u8,
+        (msg_id & 0xFF) as u8,
+        (msg_len >> 8) as u8,
+        (msg_len & 0xFF) as u8,
+        0,
+        (value.len() >> 8) as u8,
+        (value.len() & 0xFF) as u8,
+    ];
+    response.extend_from_slice(value);
+    response.extend_from_slice(&[0, 1]);
+    response
 }
 
 // Tests the `connect` method of the `OpenShowVar` struct for successful connection.
@@ -117,6 +361,27 @@ fn test_send() {
     assert!(response.is_ok());
 }
 
+// Tests that `send` reports a rejected write apart from a missing variable.
+#[test]
+fn test_send_failure_status() {
+    // Start a mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    assert!(matches!(
+        osv.send("non_existing_var", "1"),
+        Err(Error::WriteRejected { .. })
+    ));
+    assert!(matches!(
+        osv.send("non_existing_var", ""),
+        Err(Error::VariableNotFound { .. })
+    ));
+}
+
 // Tests the `read` method of the `OpenShowVar` struct.
 #[test]
 fn test_read() {
@@ -167,3 +432,1105 @@ fn test_disconnect() {
     // Check if connection is closed
     assert!(osv.conn.is_none());
 }
+
+// Tests that a value larger than a single socket read arrives in full when fragmented.
+#[test]
+fn test_read_large_fragmented_value() {
+    // Start a mock server that returns a 3000-byte value in 100-byte chunks
+    let value = vec![b'A'; 3000];
+    let listener = start_chunked_server(read_response(0, &value), 100);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    // The full value should be returned despite the fragmentation
+    let response = osv.read("$PRO_NAME");
+    assert_eq!(response.unwrap(), String::from_utf8(value).unwrap());
+}
+
+// Tests that frames larger than the configured maximum are rejected.
+#[test]
+fn test_read_frame_too_large() {
+    // Start a mock server that returns a 2000-byte value
+    let listener = start_chunked_server(read_response(0, &[b'A'; 2000]), 512);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance with a 1024-byte frame limit
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.set_max_frame_size(1024);
+    osv.connect().unwrap();
+
+    // The oversized frame should be reported as a protocol violation
+    let err = osv.read("$PRO_NAME").unwrap_err();
+    assert!(matches!(err, Error::ProtocolViolation(_)));
+}
+
+// Tests that a late reply carrying another message ID is discarded.
+#[test]
+fn test_read_discards_stale_response() {
+    // Start a mock server that sends a stale reply before the real one
+    let mut response = read_response(41, b"stale");
+    response.extend(read_response(0, b"fresh"));
+    let listener = start_chunked_server(response, 1024);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    // Only the reply matching the request ID should be returned
+    assert_eq!(osv.read("$OV_PRO").unwrap(), "fresh");
+    assert_eq!(osv.stale_responses(), 1);
+}
+
+// Tests that pipelined reads are resolved by message ID when answered out of order.
+#[test]
+fn test_read_many_out_of_order() {
+    // Start a mock server that answers three requests in reverse order
+    let listener = start_reversing_server(3);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    // Each result should belong to the variable it was requested for
+    let values: Vec<String> = osv
+        .read_many(&["$OV_PRO", "$MODE_OP", "$POS_ACT"])
+        .unwrap()
+        .into_iter()
+        .map(|value| value.unwrap())
+        .collect();
+    assert_eq!(values, vec!["$OV_PRO", "$MODE_OP", "$POS_ACT"]);
+}
+
+// Tests that a pipelined write with an empty value is rejected instead of sent as a read.
+#[test]
+fn test_pipeline_rejects_empty_write() {
+    // Start the mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    let mut pipeline = osv.pipeline();
+    pipeline
+        .write("$OV_PRO", "")
+        .write("$OV_PRO", "50")
+        .read("$MODE_OP");
+    let results = pipeline.execute().unwrap();
+    assert!(matches!(
+        results[0],
+        Err(Error::InvalidInput("Empty value"))
+    ));
+    assert_eq!(results[1].as_ref().unwrap(), "50");
+    assert_eq!(results[2].as_ref().unwrap(), "$MODE_OP");
+}
+
+// Tests that `exchange` reports a failed request through the status trailer.
+#[test]
+fn test_exchange_failure_status() {
+    // Start a mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    // The failure should be returned as a response, not as an error
+    let response = osv.exchange(Mode::Read, "non_existing_var", "").unwrap();
+    assert!(!response.success);
+    assert_eq!(response.mode, Mode::Read);
+}
+
+// Tests that an empty string value is returned as a successful read.
+#[test]
+fn test_read_empty_value() {
+    // Start a mock server that returns an empty value with the success trailer
+    let listener = start_chunked_server(read_response(0, b""), 1024);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    // The empty value should not be mistaken for a missing variable
+    assert_eq!(osv.read("$PRO_NAME[]").unwrap(), "");
+}
+
+// Tests that a rejected write is distinguished from a missing variable on read.
+#[test]
+fn test_write_rejected() {
+    // Start a mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    // The rejected write should be reported with its own error variant
+    let err = osv.write("non_existing_var", "1").unwrap_err();
+    assert!(
+        matches!(err, Error::WriteRejected { name, value } if name == "non_existing_var" && value == "1")
+    );
+}
+
+// Tests that reading a missing variable reports its name.
+#[test]
+fn test_read_variable_not_found() {
+    // Start a mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    let err = osv.read("non_existing_var").unwrap_err();
+    assert!(matches!(err, Error::VariableNotFound { name } if name == "non_existing_var"));
+}
+
+// Tests that operations without a connection report `NotConnected`.
+#[test]
+fn test_read_not_connected() {
+    let mut osv = OpenShowVar::new("127.0.0.1".to_string(), 7000);
+    assert!(matches!(osv.read("$OV_PRO"), Err(Error::NotConnected)));
+}
+
+// Tests that a server that never answers produces a timeout error.
+#[test]
+fn test_read_timeout() {
+    // Start a mock server that accepts the connection but never replies
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let _handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(5));
+        drop(stream);
+    });
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    assert!(matches!(osv.read("$OV_PRO"), Err(Error::Timeout)));
+}
+
+// Tests that an oversized value is rejected before anything is sent.
+#[test]
+fn test_write_value_too_long() {
+    // Start a mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    // A value that cannot fit into one frame should fail validation
+    let value = "A".repeat(70_000);
+    let err = osv.write("existing_var", &value).unwrap_err();
+    assert!(matches!(err, Error::ValueTooLong { len: 70_000, .. }));
+
+    // The connection should still be usable afterwards
+    assert_eq!(osv.read("existing_var").unwrap(), "existing_var");
+}
+
+// Tests that the builder applies the configured read timeout and socket options.
+#[test]
+fn test_builder_settings() {
+    // Start a mock server that accepts the connection but never replies
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let _handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(2));
+        drop(stream);
+    });
+
+    // Create a connected `OpenShowVar` instance through the builder
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .connect_timeout(Duration::from_secs(1))
+        .read_timeout(Duration::from_millis(200))
+        .nodelay(true)
+        .keepalive(Duration::from_secs(30))
+        .connect()
+        .unwrap();
+
+    // Socket options should be applied to the connection
+    let conn = osv.conn.as_ref().unwrap();
+    assert!(conn.nodelay().unwrap());
+    assert_eq!(
+        conn.read_timeout().unwrap(),
+        Some(Duration::from_millis(200))
+    );
+
+    // The read should give up after the configured timeout
+    let start = std::time::Instant::now();
+    assert!(matches!(osv.read("$OV_PRO"), Err(Error::Timeout)));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+// Tests that a read is retried on a new connection after the old one was dropped.
+#[test]
+fn test_read_reconnects() {
+    // Start a mock server that drops the first connection
+    let (listener, _handle) = start_flaky_server(1);
+    let addr = listener.local_addr().unwrap();
+
+    // Create a connected `OpenShowVar` instance with a reconnect policy
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .reconnect(
+            ReconnectPolicy::new().backoff(Duration::from_millis(10), Duration::from_millis(50)),
+        )
+        .connect()
+        .unwrap();
+
+    // The read should succeed transparently on the second connection
+    assert_eq!(osv.read("existing_var").unwrap(), "existing_var");
+    assert!(osv.is_connected());
+}
+
+// Tests that pipelined reads are retried on a new connection after the old one was dropped.
+#[test]
+fn test_read_many_reconnects() {
+    // Start a mock server that drops the first connection
+    let (listener, _handle) = start_flaky_server(1);
+    let addr = listener.local_addr().unwrap();
+
+    // Create a connected `OpenShowVar` instance with a reconnect policy
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .reconnect(
+            ReconnectPolicy::new().backoff(Duration::from_millis(10), Duration::from_millis(50)),
+        )
+        .connect()
+        .unwrap();
+
+    // The batch should succeed transparently on the second connection
+    let values = osv.read_many(&["A", "B"]).unwrap();
+    assert_eq!(values[0].as_ref().unwrap(), "A");
+    assert_eq!(values[1].as_ref().unwrap(), "B");
+    assert!(osv.is_connected());
+}
+
+// Tests that a plain timeout is returned to the caller without reconnecting.
+#[test]
+fn test_timeout_does_not_reconnect() {
+    // Start a mock server that accepts one connection but never replies
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let _handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(2));
+        drop(stream);
+    });
+
+    // Create a connected `OpenShowVar` instance with a reconnect policy
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .read_timeout(Duration::from_millis(100))
+        .reconnect(
+            ReconnectPolicy::new().backoff(Duration::from_millis(10), Duration::from_millis(50)),
+        )
+        .connect()
+        .unwrap();
+
+    // The slow server is reported as is and the connection is kept
+    let start = std::time::Instant::now();
+    let err = osv.read("$OV_PRO").unwrap_err();
+    assert!(matches!(err, Error::Timeout));
+    assert!(!err.is_disconnect());
+    assert!(start.elapsed() < Duration::from_millis(300));
+    assert!(osv.is_connected());
+}
+
+// Tests that a read cut off by a timeout mid-frame is retried on a new connection.
+#[test]
+fn test_partial_frame_timeout_reconnects() {
+    // Start a mock server that stalls longer than the read timeout mid-reply
+    let listener = start_stalling_server(Duration::from_millis(300));
+    let addr = listener.local_addr().unwrap();
+
+    // Wait until the stalled reply is over before sending the request again
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .read_timeout(Duration::from_millis(100))
+        .reconnect(
+            ReconnectPolicy::new()
+                .backoff(Duration::from_millis(400), Duration::from_millis(400))
+                .jitter(false),
+        )
+        .connect()
+        .unwrap();
+
+    assert_eq!(osv.read("A").unwrap(), "A");
+    assert!(osv.is_connected());
+}
+
+// Tests that writes are not retried unless the policy allows it.
+#[test]
+fn test_write_reconnect_policy() {
+    // Start a mock server that drops the first connection
+    let (listener, _handle) = start_flaky_server(1);
+    let addr = listener.local_addr().unwrap();
+    let policy =
+        ReconnectPolicy::new().backoff(Duration::from_millis(10), Duration::from_millis(50));
+
+    // Without write retries, the lost write is reported to the caller
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .reconnect(policy.clone())
+        .connect()
+        .unwrap();
+    assert!(osv.write("existing_var", "1").unwrap_err().is_disconnect());
+
+    // With write retries, the write is sent again on a new connection
+    let (listener, _handle) = start_flaky_server(1);
+    let addr = listener.local_addr().unwrap();
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .reconnect(policy.retry_writes(true))
+        .connect()
+        .unwrap();
+    assert_eq!(osv.write("existing_var", "1").unwrap(), "1");
+}
+
+// Tests that `is_connected` notices when the server closes the connection.
+#[test]
+fn test_is_connected_after_peer_close() {
+    // Start a mock server that closes every connection right away
+    let (listener, _handle) = start_flaky_server(usize::MAX);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    // Once the close has arrived, the connection should no longer count as active
+    thread::sleep(Duration::from_millis(100));
+    assert!(osv.conn.is_some());
+    assert!(!osv.is_connected());
+}
+
+// Tests the active health probe and the last exchange timestamp.
+#[test]
+fn test_health() {
+    // Start a mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    // Before connecting, the connection should be reported as disconnected
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    assert_eq!(osv.health(), ConnectionState::Disconnected);
+
+    // A connected server answering the probe should be healthy
+    osv.connect().unwrap();
+    assert!(osv.last_exchange().is_none());
+    assert!(osv.health().is_healthy());
+    assert!(osv.last_exchange().is_some());
+}
+
+// Tests that a server which stops answering is reported as unresponsive.
+#[test]
+fn test_health_unresponsive() {
+    // Start a mock server that accepts the connection but never replies
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let _handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(2));
+        drop(stream);
+    });
+
+    // Create a connected `OpenShowVar` instance with a short probe timeout
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .health_probe("$MODE_OP", Duration::from_millis(100))
+        .connect()
+        .unwrap();
+
+    // The open but silent connection should be reported as unresponsive
+    assert!(osv.is_connected());
+    assert_eq!(osv.health(), ConnectionState::Unresponsive);
+}
+
+// Tests that handles cloned across threads share a single connection.
+#[test]
+fn test_shared_concurrent_reads() {
+    // Start a mock server that serves only its first connection
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let _handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        serve_connection(stream);
+    });
+
+    // Create a connected `OpenShowVar` instance and share it
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+    let shared = osv.into_shared();
+
+    // Every thread should get the answers to its own requests
+    let workers: Vec<_> = (0..8)
+        .map(|worker| {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                for i in 0..20 {
+                    let name = format!("VAR_{}_{}", worker, i);
+                    assert_eq!(shared.read(&name).unwrap(), name);
+                }
+                let values = shared.read_many(&["A", "B"]).unwrap();
+                assert_eq!(values[1].as_ref().unwrap(), "B");
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    assert_eq!(shared.write("$OV_PRO", "50").unwrap(), "50");
+
+    // Disconnecting through one handle closes the connection for all of them
+    let other = shared.clone();
+    shared.disconnect();
+    assert!(!other.is_connected());
+    assert!(matches!(other.read("$OV_PRO"), Err(Error::NotConnected)));
+}
+
+// Tests that the pool spreads requests over its connections and replaces broken ones.
+#[test]
+fn test_pool() {
+    // Start a mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    // Create a pool of two connections and open them
+    let pool = OpenShowVarPool::new(OpenShowVar::builder(addr.ip().to_string(), addr.port()), 2)
+        .checkout_timeout(Duration::from_millis(200));
+    pool.connect().unwrap();
+    assert_eq!(pool.open_connections(), 2);
+    assert_eq!(pool.idle_connections(), 2);
+
+    // Batches spread over the connections should keep the request order
+    let names: Vec<String> = (0..50).map(|i| format!("VAR_{}", i)).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let values = pool.read_many(&names).unwrap();
+    for (name, value) in names.iter().zip(values) {
+        assert_eq!(&value.unwrap(), name);
+    }
+    assert_eq!(pool.write("$OV_PRO", "50").unwrap(), "50");
+
+    // With every connection checked out, `get` should time out
+    let mut first = pool.get().unwrap();
+    let second = pool.get().unwrap();
+    assert!(matches!(pool.get(), Err(Error::Timeout)));
+    drop(second);
+    assert_eq!(pool.read("$OV_PRO").unwrap(), "$OV_PRO");
+
+    // A closed connection is not returned, and a new one replaces it
+    first.disconnect();
+    drop(first);
+    assert_eq!(pool.open_connections(), 1);
+    assert_eq!(pool.check_idle().unwrap(), 0);
+    assert_eq!(pool.open_connections(), 2);
+}
+
+// Tests that idle connections closed by the server are replaced on checkout.
+#[test]
+fn test_pool_replaces_broken_idle_connection() {
+    // Start a mock server that closes its first connection after one request
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let _handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 64];
+        let len = stream.read(&mut request).unwrap();
+        stream.write_all(&process_request(&request[..len])).unwrap();
+        drop(stream);
+        for stream in listener.incoming() {
+            thread::spawn(move || serve_connection(stream.unwrap()));
+        }
+    });
+
+    // Probe every idle connection before handing it out
+    let pool = OpenShowVarPool::new(OpenShowVar::builder(addr.ip().to_string(), addr.port()), 1)
+        .idle_check(Duration::ZERO);
+    assert_eq!(pool.read("$OV_PRO").unwrap(), "$OV_PRO");
+    thread::sleep(Duration::from_millis(50));
+
+    // The broken connection should be replaced transparently
+    assert_eq!(pool.read("$MODE_OP").unwrap(), "$MODE_OP");
+    assert_eq!(pool.open_connections(), 1);
+}
+
+// Tests that typed reads parse and convert the returned KRL literals.
+#[test]
+fn test_read_as() {
+    // Start a mock server answering with a sequence of literals
+    let listener = start_scripted_server(&[
+        "75",
+        "TRUE",
+        "\"Program\"",
+        "{X 1.0, Y 2.0, Z 3.0}",
+        "#T1",
+        "FALSE",
+    ]);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    assert_eq!(osv.read_as::<f64>("$OV_PRO").unwrap(), 75.0);
+    assert!(osv.read_as::<bool>("$IN[5]").unwrap());
+    assert_eq!(osv.read_as::<String>("$PRO_NAME[]").unwrap(), "Program");
+    assert_eq!(
+        osv.read_as::<(f64, f64, f64)>("$POS_ACT").unwrap(),
+        (1.0, 2.0, 3.0)
+    );
+    assert!(matches!(
+        osv.read_as::<i32>("$MODE_OP"),
+        Err(Error::UnexpectedType {
+            expected: "i32",
+            ..
+        })
+    ));
+    assert_eq!(osv.read_as::<Option<bool>>("$OUT[1]").unwrap(), Some(false));
+}
+
+// Tests that typed writes send canonical KRL literals.
+#[test]
+fn test_write_value() {
+    // Start the mock server
+    let (listener, _handle) = start_mock_server();
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    assert_eq!(osv.write_value("$OUT[3]", true).unwrap(), "TRUE");
+    assert_eq!(osv.write_value("$OV_PRO", 50.0).unwrap(), "50.0");
+    assert_eq!(osv.write_value("$PRO_NAME[]", "Main").unwrap(), "\"Main\"");
+    assert_eq!(
+        osv.write_value("$TOOL", KrlValue::partial([("X", KrlValue::from(10))]))
+            .unwrap(),
+        "{X 10}"
+    );
+    assert!(matches!(
+        osv.write_value("$OV_PRO", None::<f64>),
+        Err(Error::InvalidInput(_))
+    ));
+
+    // Variables that do not exist are returned as `None` when reading an `Option`
+    assert_eq!(
+        osv.read_as::<Option<f64>>("non_existing_var").unwrap(),
+        None
+    );
+    assert!(matches!(
+        osv.read_as::<f64>("non_existing_var"),
+        Err(Error::VariableNotFound { .. })
+    ));
+}
+
+// Tests reading a two-dimensional array range in one batch.
+#[test]
+fn test_read_array() {
+    // Start a mock server answering the six element reads
+    let listener = start_scripted_server(&["1", "2", "3", "4", "5", "6"]);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    let parts = osv.read_array::<i32>("PARTS", &[2..=3, 1..=3]).unwrap();
+    assert_eq!(parts, vec![1, 2, 3, 4, 5, 6]);
+}
+
+// Tests that array writes address every element and that index ranges are checked.
+#[test]
+fn test_write_array() {
+    // Start a mock server that records the names of written elements
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (names, written) = std::sync::mpsc::channel();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        loop {
+            let mut header = [0; 4];
+            if stream.read_exact(&mut header).is_err() {
+                return;
+            }
+            let msg_len = ((header[2] as usize) << 8) | (header[3] as usize);
+            let mut request = header.to_vec();
+            request.resize(4 + msg_len, 0);
+            stream.read_exact(&mut request[4..]).unwrap();
+            let decoded = decode_request(&request).unwrap();
+            names.send((decoded.name, decoded.value)).unwrap();
+            stream.write_all(&process_request(&request)).unwrap();
+        }
+    });
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    // Elements whose value is `None` are skipped
+    osv.write_array("$OUT", &[5..=7], &[Some(true), None, Some(false)])
+        .unwrap();
+    let written: Vec<_> = written.try_iter().collect();
+    assert_eq!(
+        written,
+        vec![
+            ("$OUT[5]".to_string(), "TRUE".to_string()),
+            ("$OUT[7]".to_string(), "FALSE".to_string()),
+        ]
+    );
+
+    // Invalid index ranges are rejected before anything is sent
+    let invalid = |result: Result<(), Error>| matches!(result, Err(Error::InvalidInput(_)));
+    assert!(invalid(osv.write_array("$OUT", &[0..=1], &[true, true])));
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = 3..=2;
+    assert!(invalid(osv.write_array("$OUT", &[empty], &[true])));
+    assert!(invalid(osv.write_array("$OUT", &[1..=2], &[true])));
+    assert!(invalid(osv.write_array("$OUT[1]", &[1..=1], &[true])));
+    assert!(invalid(osv.write_array(
+        "CUBE",
+        &[1..=1, 1..=1, 1..=1, 1..=1],
+        &[1]
+    )));
+    assert!(matches!(
+        osv.read_array::<bool>("$IN", &[]),
+        Err(Error::InvalidInput(_))
+    ));
+
+    // Oversized ranges are rejected before any element name is built
+    assert!(matches!(
+        osv.read_array::<bool>("X", &[1..=u32::MAX]),
+        Err(Error::InvalidInput("Array index exceeds MAX_ARRAY_INDEX"))
+    ));
+    assert!(matches!(
+        osv.read_array::<bool>("CUBE", &[1..=60_000, 1..=60_000, 1..=60_000]),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(invalid(osv.write_array(
+        "CUBE",
+        &[1..=300, 1..=300],
+        &[0; 1]
+    )));
+}
+
+// Tests the digital and analog I/O facade.
+#[test]
+fn test_io() {
+    // Start a mock server answering the reads in order
+    let listener = start_scripted_server(&["TRUE", "0.5", "FALSE", "TRUE", "FALSE", "TRUE"]);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+    let mut io = osv.io();
+
+    assert!(io.digital_in(5).unwrap());
+    assert_eq!(io.analog_in(1).unwrap(), 0.5);
+    let inputs = io.digital_inputs(10, 13).unwrap();
+    assert_eq!(inputs.len(), 4);
+    assert_eq!(inputs.get(11), Some(true));
+    assert_eq!(inputs.get(9), None);
+    assert_eq!(inputs.ones().collect::<Vec<_>>(), vec![11, 13]);
+    assert_eq!(inputs.count_ones(), 2);
+
+    // Writes are answered with the written value
+    io.set_digital_out(3, true).unwrap();
+    io.set_analog_out(2, -0.25).unwrap();
+    io.set_digital_outputs(1, &[true, false]).unwrap();
+    io.pulse_out(4, Duration::from_millis(10)).unwrap();
+
+    // Numbers and values outside the KRC4 limits are rejected
+    let invalid = |result: Result<(), Error>| matches!(result, Err(Error::InvalidInput(_)));
+    assert!(invalid(io.set_digital_out(0, true)));
+    assert!(invalid(io.set_digital_out(4097, true)));
+    assert!(invalid(io.set_analog_out(33, 0.0)));
+    assert!(invalid(io.set_analog_out(1, 1.5)));
+    assert!(invalid(io.set_digital_outputs(4096, &[true, true])));
+    assert!(matches!(
+        io.digital_inputs(4000, 4097),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+// Tests typed system variable access and that read-only variables are never written.
+#[test]
+fn test_system_vars() {
+    // Start a mock server answering the reads in order
+    let listener = start_scripted_server(&["75", "#T1"]);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    assert_eq!(osv.read_system::<i32>(SystemVar::OvPro).unwrap(), 75);
+    assert_eq!(
+        osv.read_system::<KrlValue>(SystemVar::ModeOp).unwrap(),
+        KrlValue::Enum("T1".to_string())
+    );
+    assert_eq!(osv.write_system(SystemVar::OvPro, 30).unwrap(), "30");
+    assert!(matches!(
+        osv.write_system(SystemVar::AxisAct, KrlValue::partial([("A1", KrlValue::from(0))])),
+        Err(Error::ReadOnly { name }) if name == "$AXIS_ACT"
+    ));
+
+    // Every catalog entry can be found by its name
+    for var in SystemVar::ALL {
+        assert_eq!(SystemVar::from_name(var.name()), Some(*var));
+        assert!(var.name().starts_with('$'));
+    }
+    assert_eq!(SystemVar::from_name("$NOT_A_VAR"), None);
+    assert!(SystemVar::OvPro.is_writable());
+    assert!(!SystemVar::PosAct.is_writable());
+}
+
+// Tests that a snapshot reads and converts every state variable in one batch.
+#[test]
+fn test_snapshot() {
+    // Start a mock server answering the snapshot variables in order
+    let listener = start_scripted_server(&[
+        "#AUT",
+        "80",
+        "{E6POS: X 100.0, Y 0.0, Z 500.0, A 0.0, B 90.0, C 0.0, S 2, T 35, E1 0.0, E2 0.0, E3 0.0, E4 0.0, E5 0.0, E6 0.0}",
+        "{E6AXIS: A1 0.0, A2 -90.0, A3 90.0, A4 0.0, A5 0.0, A6 0.0, E1 0.0, E2 0.0, E3 0.0, E4 0.0, E5 0.0, E6 0.0}",
+        "0.25",
+        "#P_ACTIVE",
+        "\"MAIN\"",
+        "FALSE",
+        "TRUE",
+        "TRUE",
+        "TRUE",
+        "TRUE",
+        "1",
+        "2",
+    ]);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    let state = osv.snapshot().unwrap();
+    assert_eq!(state.mode_op, OperatingMode::Aut);
+    assert_eq!(state.ov_pro, 80);
+    assert_eq!(state.pos_act.z, 500.0);
+    assert_eq!(state.pos_act.t, Some(35));
+    assert_eq!(state.axis_act.a2, -90.0);
+    assert_eq!(state.vel_act, Some(0.25));
+    assert_eq!(state.pro_state, ProgramState::Active);
+    assert_eq!(state.pro_name.as_deref(), Some("MAIN"));
+    assert_eq!(state.stop_mess, Some(false));
+    assert_eq!(state.drives_on, Some(true));
+    assert_eq!(state.alarm_stop, Some(true));
+    assert_eq!((state.act_tool, state.act_base), (Some(1), Some(2)));
+    assert!(state.timestamp <= std::time::SystemTime::now());
+}
+
+// Tests that unreadable optional values do not fail the snapshot, but required ones do.
+#[test]
+fn test_snapshot_missing_values() {
+    let pos = "{E6POS: X 100.0, Y 0.0, Z 500.0, A 0.0, B 90.0, C 0.0, S 2, T 35, E1 0.0, E2 0.0, E3 0.0, E4 0.0, E5 0.0, E6 0.0}";
+    let axis = "{E6AXIS: A1 0.0, A2 -90.0, A3 90.0, A4 0.0, A5 0.0, A6 0.0, E1 0.0, E2 0.0, E3 0.0, E4 0.0, E5 0.0, E6 0.0}";
+
+    // Start a mock server with no program name and an unknown stop signal
+    let listener = start_scripted_server(&[
+        "#T1", "100", pos, axis, "0.0", "#P_FREE", "", "FALSE", "TRUE", "TRUE", "TRUE", "#ON", "1",
+        "2",
+    ]);
+    let addr = listener.local_addr().unwrap();
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    let state = osv.snapshot().unwrap();
+    assert_eq!(state.mode_op, OperatingMode::T1);
+    assert_eq!(state.pro_name, None);
+    assert_eq!(state.alarm_stop, None);
+    assert_eq!(state.drives_on, Some(true));
+
+    // A position that cannot be converted fails the whole snapshot
+    let listener = start_scripted_server(&[
+        "#T1", "100", "{X 1.0}", axis, "0.0", "#P_FREE", "\"MAIN\"", "FALSE", "TRUE", "TRUE",
+        "TRUE", "TRUE", "1", "2",
+    ]);
+    let addr = listener.local_addr().unwrap();
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    assert!(matches!(osv.snapshot(), Err(Error::MissingMember { .. })));
+}
+
+// Tests change events, deadband filtering and sharing of subscribed variables.
+#[test]
+fn test_subscriptions() {
+    // Start a mock server answering the polls in order
+    let listener = start_scripted_server(&["1.0", "1.05", "1.0", "1.5", "2"]);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance, connect it and move it to the polling thread
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+    let subscriptions = osv.into_subscriptions();
+
+    // Changes within the deadband are not reported
+    let watch = Watch::new("$VEL_ACT", Duration::from_millis(5)).deadband(0.1);
+    let velocity = subscriptions.subscribe(watch).unwrap();
+    let mut changes = Vec::new();
+    for _ in 0..3 {
+        let event = velocity.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(event.name, "$VEL_ACT");
+        changes.push((event.old, event.new));
+    }
+    assert_eq!(
+        changes,
+        vec![
+            (None, KrlValue::Real(1.0)),
+            (Some(KrlValue::Real(1.0)), KrlValue::Real(1.5)),
+            (Some(KrlValue::Real(1.5)), KrlValue::Int(2)),
+        ]
+    );
+
+    // Variables shared between subscribers are polled once
+    let callback = subscriptions
+        .subscribe_with(Watch::new("$vel_act", Duration::from_millis(5)), |_| {})
+        .unwrap();
+    assert_eq!(subscriptions.polled_variables(), 1);
+    drop(velocity);
+    assert_eq!(subscriptions.polled_variables(), 1);
+    drop(callback);
+    assert_eq!(subscriptions.polled_variables(), 0);
+
+    // Invalid watches are rejected
+    assert!(matches!(
+        subscriptions.subscribe(Watch::new("$OV_PRO", Duration::ZERO)),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        subscriptions.subscribe(Watch::new("$OV_PRO", Duration::from_millis(5)).deadband(-1.0)),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+// Tests that subscriptions resume after the proxy drops the connection.
+#[test]
+fn test_subscription_reconnects() {
+    // Start a mock server that closes the first connection after two reads
+    let listener = start_restarting_server(2);
+    let addr = listener.local_addr().unwrap();
+
+    // Create a connected `OpenShowVar` instance with a reconnect policy
+    let osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .reconnect(
+            ReconnectPolicy::new().backoff(Duration::from_millis(10), Duration::from_millis(50)),
+        )
+        .connect()
+        .unwrap();
+    let subscriptions = osv.into_subscriptions();
+
+    let counter = subscriptions
+        .subscribe(Watch::new("COUNTER", Duration::from_millis(5)))
+        .unwrap();
+    let timeout = Duration::from_secs(2);
+    let first = counter.recv_timeout(timeout).unwrap();
+    assert_eq!((first.old, first.new), (None, KrlValue::Int(1)));
+
+    // The change read on the new connection is delivered like any other
+    let second = counter.recv_timeout(timeout).unwrap();
+    assert_eq!(
+        (second.old, second.new),
+        (Some(KrlValue::Int(1)), KrlValue::Int(2))
+    );
+}
+
+// Tests that callbacks receive changes and that events end when polling stops.
+#[test]
+fn test_subscription_callback() {
+    // Start a mock server answering the polls in order
+    let listener = start_scripted_server(&["TRUE", "TRUE", "FALSE"]);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance, connect it and move it to the polling thread
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+    let subscriptions = osv.into_subscriptions();
+
+    let (sender, events) = std::sync::mpsc::channel();
+    let watch = Watch::new("$IN[1]", Duration::from_millis(5));
+    let _input = subscriptions
+        .subscribe_with(watch, move |event| sender.send(event.new.clone()).unwrap())
+        .unwrap();
+    let timeout = Duration::from_secs(2);
+    assert_eq!(events.recv_timeout(timeout).unwrap(), KrlValue::Bool(true));
+    assert_eq!(events.recv_timeout(timeout).unwrap(), KrlValue::Bool(false));
+
+    // Reads of the exhausted script fail to parse and are skipped
+    thread::sleep(Duration::from_millis(30));
+    assert!(subscriptions.failed_reads() > 0);
+
+    // Channel subscriptions end once the subscriptions are dropped
+    let pending = subscriptions
+        .subscribe(Watch::new("$IN[2]", Duration::from_millis(5)))
+        .unwrap();
+    drop(subscriptions);
+    assert_eq!(pending.iter().count(), 0);
+}
+
+// Tests that samples are taken on a fixed schedule and their timing is recorded.
+#[test]
+fn test_sampler() {
+    // Start a mock server answering the samples in order
+    let listener = start_scripted_server(&["1", "2", "3"]);
+    let addr = listener.local_addr().unwrap();
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    let period = Duration::from_millis(50);
+    let mut sampler = osv.sampler(&["$OV_PRO"], period).unwrap();
+    let samples: Vec<_> = sampler.by_ref().take(3).map(Result::unwrap).collect();
+    for (index, sample) in samples.iter().enumerate() {
+        assert_eq!(sample.index, index as u64);
+        assert!(!sample.overrun);
+        assert!(sample.started >= sample.scheduled);
+    }
+    assert_eq!(samples[2].scheduled - samples[0].scheduled, period * 2);
+    let values: Vec<_> = samples[1]
+        .values
+        .iter()
+        .map(|val| val.as_ref().unwrap())
+        .collect();
+    assert_eq!(values, vec!["2"]);
+
+    let stats = sampler.stats();
+    assert_eq!((stats.samples, stats.overruns, stats.skipped), (3, 0, 0));
+    assert_eq!(stats.jitter.count(), 3);
+    assert_eq!(stats.latency.count(), 3);
+    assert!(stats.latency.max() >= stats.latency.min());
+
+    // Invalid sampler settings are rejected
+    assert!(matches!(
+        osv.sampler(&[], period),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        osv.sampler(&["$OV_PRO"], Duration::ZERO),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+// Tests that samples slower than the period are reported as overruns.
+#[test]
+fn test_sampler_overrun() {
+    // Start a mock server that answers each request after 30 ms
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        loop {
+            let mut header = [0; 4];
+            if stream.read_exact(&mut header).is_err() {
+                return;
+            }
+            let msg_len = ((header[2] as usize) << 8) | (header[3] as usize);
+            let mut request = header.to_vec();
+            request.resize(4 + msg_len, 0);
+            stream.read_exact(&mut request[4..]).unwrap();
+            thread::sleep(Duration::from_millis(30));
+            stream.write_all(&process_request(&request)).unwrap();
+        }
+    });
+
+    // Create an `OpenShowVar` instance and connect to the mock server
+    let mut osv = OpenShowVar::new(addr.ip().to_string(), addr.port());
+    osv.connect().unwrap();
+
+    let mut sampler = osv
+        .sampler(&["$OV_PRO"], Duration::from_millis(10))
+        .unwrap();
+    let first = sampler.next_sample().unwrap();
+    let second = sampler.next_sample().unwrap();
+    assert!(first.overrun);
+    assert!(second.index >= 3);
+    assert!(first.latency >= Duration::from_millis(30));
+
+    let stats = sampler.stats();
+    assert_eq!(stats.overruns, 2);
+    assert!(stats.skipped >= 4);
+    assert!(stats.latency.percentile(50.0).unwrap() >= Duration::from_millis(50));
+}
+
+// Tests that a timeout in the middle of a frame drops the misaligned connection.
+#[test]
+fn test_partial_frame_timeout_drops_connection() {
+    // Start a mock server that stalls longer than the read timeout mid-reply
+    let listener = start_stalling_server(Duration::from_millis(300));
+    let addr = listener.local_addr().unwrap();
+
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .read_timeout(Duration::from_millis(100))
+        .connect()
+        .unwrap();
+
+    assert!(matches!(osv.read("A"), Err(Error::Timeout)));
+    assert!(!osv.is_connected());
+
+    // The rest of the cut-off frame is never taken for a new header
+    thread::sleep(Duration::from_millis(300));
+    assert!(matches!(osv.read("B"), Err(Error::NotConnected)));
+}
+
+// Tests that a failed write drops the connection instead of leaving a half-sent frame behind.
+#[test]
+fn test_failed_write_drops_connection() {
+    // Start a mock server that resets the connection before any request arrives
+    let listener = start_resetting_server();
+    let addr = listener.local_addr().unwrap();
+
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .connect()
+        .unwrap();
+    thread::sleep(Duration::from_millis(300));
+
+    assert!(matches!(osv.write("A", "1"), Err(Error::Io(_))));
+    assert!(!osv.is_connected());
+
+    // The next request is not written to the broken socket
+    assert!(matches!(osv.read("A"), Err(Error::NotConnected)));
+}
+
+// Tests that sampling resumes after the proxy drops the connection.
+#[test]
+fn test_sampler_reconnects() {
+    // Start a mock server that closes the first connection after two reads
+    let listener = start_restarting_server(2);
+    let addr = listener.local_addr().unwrap();
+
+    // Create a connected `OpenShowVar` instance with a reconnect policy
+    let mut osv = OpenShowVar::builder(addr.ip().to_string(), addr.port())
+        .reconnect(
+            ReconnectPolicy::new().backoff(Duration::from_millis(10), Duration::from_millis(50)),
+        )
+        .connect()
+        .unwrap();
+
+    let mut sampler = osv
+        .sampler(&["COUNTER"], Duration::from_millis(20))
+        .unwrap();
+    let values: Vec<String> = sampler
+        .by_ref()
+        .take(4)
+        .map(|sample| sample.unwrap().values.remove(0).unwrap())
+        .collect();
+    assert_eq!(values, vec!["1", "1", "2", "2"]);
+    assert_eq!(sampler.stats().samples, 4);
+}